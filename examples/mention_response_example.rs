@@ -1,8 +1,7 @@
+use anyhow::Context;
 use axum::{routing::get, Router};
 use ngrok::prelude::*;
-use slack_rs::{
-    create_app_with_path, Event, MessageClient, SigningSecret, SlackEventHandler, Token,
-};
+use slack_rs::{create_app_with_path, Event, MessageClient, SlackConfig, SlackEventHandler};
 use std::net::SocketAddr;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
@@ -48,22 +47,23 @@ async fn main() -> anyhow::Result<()> {
 
     info!("メンション応答サーバーを起動します");
 
-    // 環境変数からSlack認証情報を取得
-    let signing_secret =
-        std::env::var("SLACK_SIGNING_SECRET").expect("SLACK_SIGNING_SECRETが設定されていません");
-    let bot_token = std::env::var("SLACK_BOT_TOKEN").expect("SLACK_BOT_TOKENが設定されていません");
-    let bot_token = Token::new(bot_token);
+    // 環境変数からSlack認証情報を取得（不足している項目はまとめてエラーになります）
+    let config = SlackConfig::from_env()?;
 
-    let ngrok_domain = std::env::var("NGROK_DOMAIN").expect("NGROK_DOMAINが設定されていません");
+    let ngrok_domain = std::env::var("NGROK_DOMAIN").context("NGROK_DOMAINが設定されていません")?;
 
     // ルーターの設定
     let router = Router::new()
         .route("/health", get(|| async { "OK" }))
         .merge(create_app_with_path(
-            SigningSecret::new(signing_secret),
-            bot_token,
+            config
+                .signing_secret
+                .context("SLACK_SIGNING_SECRETが設定されていません")?,
+            config
+                .bot_token
+                .context("SLACK_BOT_TOKENが設定されていません")?,
             MentionHandler,
-            "/push",
+            &config.events_path,
         ));
 
     // サーバーアドレスの設定
@@ -87,8 +87,7 @@ async fn main() -> anyhow::Result<()> {
     // サーバーの起動
     axum::Server::builder(tun)
         .serve(router.into_make_service_with_connect_info::<SocketAddr>())
-        .await
-        .unwrap();
+        .await?;
 
     Ok(())
 }
@@ -1,19 +1,24 @@
-use slack_rs::{Block, MessageClient, Token};
+use slack_rs::{Block, ChannelId, MessageClient, SlackConfig, TextObject};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let token = std::env::var("SLACK_BOT_TOKEN").expect("SLACK_BOT_TOKEN must be set");
-    let token = Token::new(token);
-    let client = MessageClient::new(token);
+    // SLACK_BOT_TOKENとSLACK_SIGNING_SECRET（Socket Modeの場合はSLACK_APP_TOKEN）が必要です
+    let config = SlackConfig::from_env()?;
+    let client = MessageClient::new(
+        config
+            .bot_token
+            .ok_or("SLACK_BOT_TOKENが設定されていません")?,
+    )?;
+    let channel: ChannelId = std::env::var("SLACK_CHANNEL_ID")?.parse()?;
 
     // テキストメッセージの送信
-    client.send_text("#general", "Hello, World!").await?;
+    client.send_text(&channel, "Hello, World!").await?;
 
     // ブロックキットを使用したメッセージ
-    let blocks = vec![Block::Section {
-        text: "*Bold* _italic_ ~strike~".to_string(),
-    }];
-    client.send_blocks("#general", blocks).await?;
+    let blocks = vec![Block::section(TextObject::mrkdwn(
+        "*Bold* _italic_ ~strike~",
+    ))];
+    client.send_blocks(&channel, blocks).await?;
 
     Ok(())
 }
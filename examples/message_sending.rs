@@ -1,54 +1,51 @@
-use slack_rs::{Block, MessageClient, Token};
+use slack_rs::{Block, ChannelId, MessageClient, SlackConfig, TextObject};
 use std::time::Duration;
 use tokio::time::sleep;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let token = std::env::var("SLACK_BOT_TOKEN").expect("SLACK_BOT_TOKEN must be set");
-    let token = Token::new(token);
-    let client = MessageClient::new(token);
+    // SLACK_BOT_TOKENとSLACK_SIGNING_SECRET（Socket Modeの場合はSLACK_APP_TOKEN）が必要です
+    let config = SlackConfig::from_env()?;
+    let client = MessageClient::new(
+        config
+            .bot_token
+            .ok_or("SLACK_BOT_TOKENが設定されていません")?,
+    )?;
+    let channel: ChannelId = "C087D6X8NM9".parse()?;
 
     // テキストメッセージの送信
     client
-        .send_text("C087D6X8NM9", "基本的なテキストメッセージ")
+        .send_text(&channel, "基本的なテキストメッセージ")
         .await?;
 
     // ブロックキットを使用したメッセージ
     let blocks = vec![
-        Block::Section {
-            text: "*太字* _斜体_ ~取り消し線~".to_string(),
-        },
-        Block::Section {
-            text: "プレーンテキスト".to_string(),
-        },
+        Block::section(TextObject::mrkdwn("*太字* _斜体_ ~取り消し線~")),
+        Block::section(TextObject::plain("プレーンテキスト")),
     ];
-    client.send_blocks("C087D6X8NM9", blocks).await?;
+    client.send_blocks(&channel, blocks).await?;
 
     // スレッド返信
+    let parent = client.send_text(&channel, "スレッドの親メッセージ").await?;
     client
-        .send_text("C087D6X8NM9", "スレッドの親メッセージ")
-        .await?;
-    client
-        .reply_to_thread("C087D6X8NM9", "1234567890.123456", "スレッドへの返信")
+        .reply_to_thread(&parent.channel, &parent.ts, "スレッドへの返信")
         .await?;
 
     // メッセージの更新と削除
-    client
-        .send_text("C087D6X8NM9", "このメッセージは更新されます")
+    let message = client
+        .send_text(&channel, "このメッセージは更新されます")
         .await?;
     sleep(Duration::from_secs(2)).await;
     client
-        .update_message("C087D6X8NM9", "1234567890.123456", "更新されたメッセージ")
+        .update_message(&message.channel, &message.ts, "更新されたメッセージ")
         .await?;
     sleep(Duration::from_secs(2)).await;
-    client
-        .delete_message("C087D6X8NM9", "1234567890.123456")
-        .await?;
+    client.delete_message(&message.channel, &message.ts).await?;
 
     // ファイルのアップロード
     let file_content = "テストファイルの内容".as_bytes().to_vec();
     client
-        .upload_file(vec!["C087D6X8NM9".to_string()], file_content, "test.txt")
+        .upload_file(vec![channel.clone()], file_content, "test.txt")
         .await?;
 
     Ok(())
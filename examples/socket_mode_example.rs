@@ -1,8 +1,7 @@
 use dotenvy::dotenv;
-use slack_rs::socket_mode::SocketModeClient;
-use std::env;
-use tokio;
-use tracing::info;
+use slack_rs::socket_mode::{ConnectionState, SocketModeClient, SocketModeOptions};
+use slack_rs::SlackConfig;
+use tracing::{info, warn};
 
 #[cfg(feature = "socket_mode")]
 #[tokio::main]
@@ -13,18 +12,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables from .env file
     dotenv().ok();
 
-    // Get the Slack App Token from environment variables
-    let app_token = env::var("SLACK_APP_TOKEN").expect("SLACK_APP_TOKEN must be set in .env file");
+    // Load the Slack settings (requires SLACK_SOCKET_MODE=1, SLACK_APP_TOKEN and SLACK_BOT_TOKEN)
+    let config = SlackConfig::from_env()?;
+    let app_token = config
+        .app_token
+        .ok_or("SLACK_APP_TOKEN must be set with SLACK_SOCKET_MODE=1")?;
 
     info!("Starting Socket Mode client...");
 
-    // Create and start the Socket Mode client
-    let client = SocketModeClient::new(&app_token);
-    client.connect().await?;
-
-    // Keep the connection alive
+    // Create and start the Socket Mode client with two parallel connections
+    let client =
+        SocketModeClient::new(app_token.expose_secret())?.with_options(SocketModeOptions {
+            connections: 2,
+            ..Default::default()
+        });
+    let handle = client.connect().await?;
+
+    // Log connection state changes
+    let mut state = handle.state();
+    tokio::spawn(async move {
+        while state.changed().await.is_ok() {
+            match &*state.borrow_and_update() {
+                ConnectionState::Reconnecting { reason } => warn!("Reconnecting: {}", reason),
+                state => info!("Connection state: {:?}", state),
+            }
+        }
+    });
+
+    // Keep the connection alive until Ctrl+C, then finish in-flight events and close
     tokio::signal::ctrl_c().await?;
     info!("Shutting down...");
+    handle.shutdown().await;
 
     Ok(())
 }
@@ -1,5 +1,6 @@
+use anyhow::Context;
 use axum::{routing::get, Router};
-use slack_rs::{create_app, SigningSecret};
+use slack_rs::{create_app, SlackConfig};
 use std::net::SocketAddr;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
@@ -18,16 +19,18 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Webhookサーバーを起動します");
 
-    // 環境変数からSlack署名シークレットを取得
-    let signing_secret =
-        std::env::var("SLACK_SIGNING_SECRET").expect("SLACK_SIGNING_SECRETが設定されていません");
+    // 環境変数からSlackの設定を取得（不足している項目はまとめてエラーになります）
+    let config = SlackConfig::from_env()?;
+    let signing_secret = config
+        .signing_secret
+        .context("SLACK_SIGNING_SECRETが設定されていません")?;
 
-    let ngrok_domain = std::env::var("NGROK_DOMAIN").expect("NGROK_DOMAINが設定されていません");
+    let ngrok_domain = std::env::var("NGROK_DOMAIN").context("NGROK_DOMAINが設定されていません")?;
 
     // ルーターの設定
     let router = Router::new()
         .route("/health", get(|| async { "OK" }))
-        .merge(create_app(SigningSecret::new(signing_secret)));
+        .merge(create_app(signing_secret));
 
     // サーバーアドレスの設定
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
@@ -50,8 +53,7 @@ async fn main() -> anyhow::Result<()> {
     // サーバーの起動
     axum::Server::builder(tun)
         .serve(router.into_make_service_with_connect_info::<SocketAddr>())
-        .await
-        .unwrap();
+        .await?;
 
     Ok(())
 }
@@ -0,0 +1,979 @@
+//! webhookとSocket Modeで共通のボットランタイム
+//!
+//! [`SlackApp`]にハンドラ・トークン・ミドルウェアを登録しておけば、
+//! 起動方法を選ぶだけで同じアプリケーションをHTTPでもSocket Modeでも動かせます。
+//! イベントに加えて、スラッシュコマンドとインタラクションもどちらの方法でもハンドラに届きます。
+//!
+//! ```no_run
+//! use slack_rs::{NoopHandler, SlackApp, SlackConfig};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = SlackConfig::from_env()?;
+//! // SLACK_SOCKET_MODEに応じてHTTPサーバーかSocket Modeで起動します
+//! SlackApp::from_config(NoopHandler, &config).run(&config).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "config")]
+use crate::config::SlackConfig;
+#[cfg(feature = "socket_mode")]
+use crate::socket_mode::{ConnectionState, SocketModeClient, SocketModeEvent, SocketModeHandler};
+use crate::{
+    events::Event,
+    manifest::AppManifest,
+    message::MessageClient,
+    oauth::OAuthConfig,
+    types::{InvalidTokenKind, SigningSecret, TeamId, Token},
+    webhook::{
+        verify_request, SlackEventHandler, DEFAULT_COMMANDS_PATH, DEFAULT_INTERACTIONS_PATH,
+        DEFAULT_WEBHOOK_PATH,
+    },
+};
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Response,
+    routing::post,
+    Router,
+};
+use bytes::Bytes;
+use serde::Deserialize;
+use serde_json::Value;
+use slack_morphism::events::{SlackCommandEvent, SlackInteractionEvent};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info};
+
+/// 再送されたイベントを無視する期間の既定値
+///
+/// Slackはイベントの受信に失敗すると、数分以内に最大3回まで再送します。
+pub const DEFAULT_DEDUP_TTL: Duration = Duration::from_secs(600);
+
+/// イベントの前後に処理を挟むミドルウェア
+///
+/// ログの記録や、特定のチャンネル・ユーザーからのイベントの除外などに使います。
+#[async_trait]
+pub trait Middleware: Send + Sync + 'static {
+    /// ハンドラの前に呼び出されます
+    ///
+    /// `false`を返すと、イベントをハンドラに渡さずに破棄します。
+    async fn before(&self, _event: &Event) -> bool {
+        true
+    }
+
+    /// ハンドラの後に呼び出されます
+    ///
+    /// `error`はハンドラが失敗した場合のエラーメッセージです。
+    async fn after(&self, _event: &Event, _error: Option<&str>) {}
+}
+
+/// アプリを起動できない設定の場合のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidApp {
+    /// HTTPで受信するのに署名シークレットが設定されていない
+    MissingSigningSecret,
+    /// ボットトークンにアプリレベルトークンなどが指定された
+    BotToken(InvalidTokenKind),
+}
+
+impl fmt::Display for InvalidApp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSigningSecret => write!(f, "署名シークレットが設定されていません"),
+            Self::BotToken(e) => write!(f, "ボットトークンが正しくありません: {}", e),
+        }
+    }
+}
+
+impl Error for InvalidApp {}
+
+impl From<InvalidTokenKind> for InvalidApp {
+    fn from(e: InvalidTokenKind) -> Self {
+        Self::BotToken(e)
+    }
+}
+
+/// webhookとSocket Modeのどちらでも動くSlackアプリ
+///
+/// ハンドラ、トークンの解決方法、ミドルウェア、再送の重複排除をまとめて持ち、
+/// [`serve_http`](Self::serve_http)、[`try_into_router`](Self::try_into_router)、
+/// [`run_socket_mode`](Self::run_socket_mode)のいずれかで起動します。
+///
+/// ミドルウェアと重複排除が適用されるのはイベントのみで、
+/// スラッシュコマンドとインタラクションはそのままハンドラに渡します。
+pub struct SlackApp<H: SlackEventHandler> {
+    handler: H,
+    bot_token: Option<Token>,
+    oauth: Option<OAuthConfig>,
+    signing_secret: Option<SigningSecret>,
+    events_path: String,
+    commands_path: String,
+    interactions_path: String,
+    middleware: Vec<Arc<dyn Middleware>>,
+    dedup_ttl: Duration,
+}
+
+impl<H: SlackEventHandler> SlackApp<H> {
+    /// ハンドラを指定してアプリを作成します
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler,
+            bot_token: None,
+            oauth: None,
+            signing_secret: None,
+            events_path: DEFAULT_WEBHOOK_PATH.to_string(),
+            commands_path: DEFAULT_COMMANDS_PATH.to_string(),
+            interactions_path: DEFAULT_INTERACTIONS_PATH.to_string(),
+            middleware: Vec::new(),
+            dedup_ttl: DEFAULT_DEDUP_TTL,
+        }
+    }
+
+    /// 設定からボットトークン・署名シークレット・各エンドポイントのパスを読み込んでアプリを作成します
+    #[cfg(feature = "config")]
+    pub fn from_config(handler: H, config: &SlackConfig) -> Self {
+        let mut app = Self::new(handler)
+            .with_events_path(&config.events_path)
+            .with_commands_path(&config.commands_path)
+            .with_interactions_path(&config.interactions_path);
+        app.bot_token = config.bot_token.clone();
+        app.signing_secret = config.signing_secret.clone();
+        app
+    }
+
+    /// ハンドラに渡すクライアントのボットトークンを設定します
+    pub fn with_bot_token(mut self, bot_token: Token) -> Self {
+        self.bot_token = Some(bot_token);
+        self
+    }
+
+    /// ワークスペースごとのトークンをOAuthのトークンストレージから取得します
+    ///
+    /// ストレージにトークンがないワークスペースでは、ボットトークンを使用します。
+    pub fn with_oauth(mut self, oauth: OAuthConfig) -> Self {
+        self.oauth = Some(oauth);
+        self
+    }
+
+    /// HTTPで受信する場合の署名シークレットを設定します
+    pub fn with_signing_secret(mut self, signing_secret: SigningSecret) -> Self {
+        self.signing_secret = Some(signing_secret);
+        self
+    }
+
+    /// HTTPで受信する場合のイベントのパスを設定します（既定は[`DEFAULT_WEBHOOK_PATH`]）
+    pub fn with_events_path(mut self, path: &str) -> Self {
+        self.events_path = path.to_string();
+        self
+    }
+
+    /// HTTPで受信する場合のスラッシュコマンドのパスを設定します（既定は[`DEFAULT_COMMANDS_PATH`]）
+    pub fn with_commands_path(mut self, path: &str) -> Self {
+        self.commands_path = path.to_string();
+        self
+    }
+
+    /// HTTPで受信する場合のインタラクションのパスを設定します（既定は[`DEFAULT_INTERACTIONS_PATH`]）
+    pub fn with_interactions_path(mut self, path: &str) -> Self {
+        self.interactions_path = path.to_string();
+        self
+    }
+
+    /// ミドルウェアを追加します（追加した順に呼び出されます）
+    ///
+    /// ミドルウェアが呼び出されるのはイベントのみです。
+    /// スラッシュコマンドとインタラクションには適用されません。
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// 同じ`event_id`のイベントを無視する期間を設定します（`Duration::ZERO`で無効）
+    ///
+    /// 重複排除の対象はイベントのみで、スラッシュコマンドとインタラクションは再送されても毎回処理します。
+    pub fn with_dedup_ttl(mut self, ttl: Duration) -> Self {
+        self.dedup_ttl = ttl;
+        self
+    }
+
+    /// 登録したハンドラと受信するパスからマニフェストを作成します
+    ///
+    /// ハンドラの[`manifest`](SlackEventHandler::manifest)が宣言したイベント・スラッシュコマンド・
+    /// インタラクションを集めます。`base_url`を指定するとHTTPで受信するリクエストURLを
+    /// このアプリのパスで設定し、`None`の場合はSocket Modeを有効にします。
+    pub fn manifest(&self, name: &str, base_url: Option<&str>) -> AppManifest {
+        let manifest = AppManifest::new(name).with_handler(&self.handler);
+        match base_url {
+            Some(base_url) => manifest.with_request_urls(
+                base_url,
+                &self.events_path,
+                &self.interactions_path,
+                &self.commands_path,
+            ),
+            None => manifest.with_socket_mode(true),
+        }
+    }
+
+    /// イベントを処理するランタイムを作成します
+    fn into_runtime(self) -> Result<(Arc<Runtime<H>>, Option<SigningSecret>), InvalidTokenKind> {
+        let bot = self.bot_token.map(MessageClient::new).transpose()?;
+        let runtime = Runtime {
+            handler: self.handler,
+            tokens: Tokens {
+                bot,
+                oauth: self.oauth,
+                clients: Mutex::new(HashMap::new()),
+            },
+            middleware: self.middleware,
+            dedup: Dedup {
+                ttl: self.dedup_ttl,
+                seen: Mutex::new(HashMap::new()),
+            },
+        };
+        Ok((Arc::new(runtime), self.signing_secret))
+    }
+
+    /// HTTPでイベント・スラッシュコマンド・インタラクションを受信するルーターを作成します
+    ///
+    /// 他のルートと組み合わせる場合に使用します。
+    /// 署名シークレットが設定されていない場合と、
+    /// ボットトークンにアプリレベルトークン（`xapp-`）を指定した場合はエラーを返します。
+    pub fn try_into_router(self) -> Result<Router, InvalidApp> {
+        let paths = [
+            self.events_path.clone(),
+            self.commands_path.clone(),
+            self.interactions_path.clone(),
+        ];
+        let (runtime, signing_secret) = self.into_runtime()?;
+        let state = HttpState {
+            runtime,
+            signing_secret: signing_secret.ok_or(InvalidApp::MissingSigningSecret)?,
+        };
+        let [events, commands, interactions] = paths;
+        Ok(Router::new()
+            .route(&events, post(handle_http::<H>))
+            .route(&commands, post(handle_http_command::<H>))
+            .route(&interactions, post(handle_http_interaction::<H>))
+            .with_state(state))
+    }
+
+    /// [`try_into_router`](Self::try_into_router)と同じルーターを作成します
+    ///
+    /// 設定の誤りをエラーとして扱う場合は[`try_into_router`](Self::try_into_router)を使用してください。
+    ///
+    /// # Panics
+    /// [`try_into_router`](Self::try_into_router)がエラーを返す場合
+    pub fn into_router(self) -> Router {
+        self.try_into_router().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// HTTPサーバーを起動し、Ctrl+Cを受け取るまでイベントを受信します
+    pub async fn serve_http(self, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+        info!(
+            "HTTPでイベントの受信を開始します: {}{}",
+            addr, self.events_path
+        );
+        axum::Server::bind(&addr)
+            .serve(self.try_into_router()?.into_make_service())
+            .with_graceful_shutdown(async {
+                tokio::signal::ctrl_c().await.ok();
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Socket Modeで接続し、Ctrl+Cを受け取るまでイベントを受信します
+    ///
+    /// 終了時は処理中のイベントの完了を待ってから切断します。
+    #[cfg(feature = "socket_mode")]
+    pub async fn run_socket_mode(self, app_token: &str) -> Result<(), Box<dyn Error>> {
+        let client = SocketModeClient::new(app_token)?;
+        let (runtime, _) = self.into_runtime()?;
+        let handle = client.connect_with(SocketAdapter(runtime)).await?;
+        info!("Socket Modeでイベントの受信を開始しました");
+        let stopped = tokio::select! {
+            _ = tokio::signal::ctrl_c() => None,
+            state = handle.closed() => Some(state),
+        };
+        if let Some(ConnectionState::Disconnected { reason }) = stopped {
+            return Err(format!("Socket Modeの接続が終了しました: {}", reason).into());
+        }
+        handle.shutdown().await;
+        Ok(())
+    }
+
+    /// 設定に応じてHTTPサーバーまたはSocket Modeで起動します
+    ///
+    /// `socket_mode`が有効な場合はアプリレベルトークンでSocket Modeに接続し、
+    /// それ以外は`listen_addr`でHTTPサーバーを起動します。
+    #[cfg(all(feature = "config", feature = "socket_mode"))]
+    pub async fn run(self, config: &SlackConfig) -> Result<(), Box<dyn Error>> {
+        if config.socket_mode {
+            let app_token = config
+                .app_token
+                .as_ref()
+                .ok_or("SLACK_APP_TOKENが設定されていません")?;
+            self.run_socket_mode(app_token.expose_secret()).await
+        } else {
+            self.serve_http(config.listen_addr).await
+        }
+    }
+}
+
+/// 起動したアプリの状態（どの受信方法でも共通）
+struct Runtime<H: SlackEventHandler> {
+    handler: H,
+    tokens: Tokens,
+    middleware: Vec<Arc<dyn Middleware>>,
+    dedup: Dedup,
+}
+
+/// 重複排除に使う`event_id`
+#[derive(Deserialize)]
+struct EventId {
+    event_id: Option<String>,
+}
+
+impl<H: SlackEventHandler> Runtime<H> {
+    /// イベントのJSONを処理します
+    ///
+    /// URL検証の場合はチャレンジ値を返します。
+    async fn dispatch(&self, body: &str) -> Result<Option<String>, serde_json::Error> {
+        let event = Event::from_json(body)?;
+        match &event {
+            Event::UrlVerification { challenge } => return Ok(Some(challenge.clone())),
+            Event::Other => {
+                debug!("未対応のイベントタイプを受信");
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        if let Some(event_id) = serde_json::from_str::<EventId>(body)?.event_id {
+            if !self.dedup.first_seen(&event_id) {
+                info!("再送されたイベントを無視します: {}", event_id);
+                return Ok(None);
+            }
+        }
+
+        for middleware in &self.middleware {
+            if !middleware.before(&event).await {
+                debug!("ミドルウェアがイベントを破棄しました");
+                return Ok(None);
+            }
+        }
+
+        let result = match self.tokens.client_for(event.team_id()).await {
+            Ok(client) => self
+                .handler
+                .handle_event(event.clone(), &client)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            error!("イベントの処理に失敗: {}", e);
+        }
+
+        for middleware in &self.middleware {
+            middleware
+                .after(&event, result.as_ref().err().map(String::as_str))
+                .await;
+        }
+        Ok(None)
+    }
+
+    /// スラッシュコマンドを処理し、ハンドラの応答を返します
+    ///
+    /// ミドルウェアと重複排除は適用せず、ハンドラを直接呼び出します。
+    async fn dispatch_command(&self, command: SlackCommandEvent) -> Option<Value> {
+        let team_id = TeamId::from(command.team_id.clone());
+        let result = match self.tokens.client_for(Some(&team_id)).await {
+            Ok(client) => self
+                .handler
+                .handle_command(command, &client)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        result.unwrap_or_else(|e| {
+            error!("スラッシュコマンドの処理に失敗: {}", e);
+            None
+        })
+    }
+
+    /// インタラクションのJSONを処理し、ハンドラの応答を返します
+    ///
+    /// ミドルウェアと重複排除は適用せず、ハンドラを直接呼び出します。
+    async fn dispatch_interaction(
+        &self,
+        payload: Value,
+    ) -> Result<Option<Value>, serde_json::Error> {
+        let team_id = payload
+            .pointer("/team/id")
+            .and_then(Value::as_str)
+            .and_then(|id| id.parse::<TeamId>().ok());
+        let interaction: SlackInteractionEvent = serde_json::from_value(payload)?;
+        let result = match self.tokens.client_for(team_id.as_ref()).await {
+            Ok(client) => self
+                .handler
+                .handle_interaction(interaction, &client)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        Ok(result.unwrap_or_else(|e| {
+            error!("インタラクションの処理に失敗: {}", e);
+            None
+        }))
+    }
+}
+
+/// ハンドラに渡すクライアントのトークンを解決します
+struct Tokens {
+    bot: Option<MessageClient>,
+    oauth: Option<OAuthConfig>,
+    /// チームIDごとのクライアントと、その作成に使ったトークン
+    clients: Mutex<HashMap<String, (String, MessageClient)>>,
+}
+
+impl Tokens {
+    async fn client_for(&self, team_id: Option<&TeamId>) -> Result<MessageClient, String> {
+        if let (Some(oauth), Some(team_id)) = (&self.oauth, team_id) {
+            let token = oauth
+                .token_storage
+                .read()
+                .await
+                .get_token(team_id.as_str())
+                .map(|token| token.access_token);
+            if let Some(token) = token {
+                let mut clients = self.clients.lock().unwrap();
+                match clients.get(team_id.as_str()) {
+                    Some((cached, client)) if *cached == token => return Ok(client.clone()),
+                    _ => {
+                        let client =
+                            MessageClient::new(Token::new(&token)).map_err(|e| e.to_string())?;
+                        clients.insert(team_id.to_string(), (token, client.clone()));
+                        return Ok(client);
+                    }
+                }
+            }
+        }
+        self.bot.clone().ok_or_else(|| match team_id {
+            Some(team_id) => format!("チーム{}のトークンがありません", team_id),
+            None => "ボットトークンが設定されていません".to_string(),
+        })
+    }
+}
+
+/// 同じ`event_id`のイベントを一定期間記録し、再送を見分けます
+struct Dedup {
+    ttl: Duration,
+    seen: Mutex<HashMap<String, Instant>>,
+}
+
+impl Dedup {
+    /// 期間内に初めて受信したイベントであれば`true`を返します
+    fn first_seen(&self, event_id: &str) -> bool {
+        if self.ttl.is_zero() {
+            return true;
+        }
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap();
+        seen.retain(|_, received| now.duration_since(*received) < self.ttl);
+        seen.insert(event_id.to_string(), now).is_none()
+    }
+}
+
+struct HttpState<H: SlackEventHandler> {
+    runtime: Arc<Runtime<H>>,
+    signing_secret: SigningSecret,
+}
+
+impl<H: SlackEventHandler> Clone for HttpState<H> {
+    fn clone(&self) -> Self {
+        Self {
+            runtime: self.runtime.clone(),
+            signing_secret: self.signing_secret.clone(),
+        }
+    }
+}
+
+/// HTTPで受信したイベントを検証してランタイムに渡します
+async fn handle_http<H: SlackEventHandler>(
+    State(state): State<HttpState<H>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response<Body> {
+    let body = String::from_utf8(body.to_vec()).unwrap_or_default();
+    if let Err(response) = verify_request(&state.signing_secret, &headers, &body) {
+        return response;
+    }
+    match state.runtime.dispatch(&body).await {
+        Ok(Some(challenge)) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/plain")
+            .body(Body::from(challenge))
+            .unwrap(),
+        Ok(None) => Response::builder()
+            .status(StatusCode::OK)
+            .body(Body::empty())
+            .unwrap(),
+        Err(e) => bad_request(e),
+    }
+}
+
+/// インタラクションのリクエストボディ（`payload`にJSONが入ったフォーム）
+#[derive(Deserialize)]
+struct InteractionForm {
+    payload: String,
+}
+
+/// HTTPで受信したスラッシュコマンドを検証してランタイムに渡します
+async fn handle_http_command<H: SlackEventHandler>(
+    State(state): State<HttpState<H>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response<Body> {
+    let body = String::from_utf8(body.to_vec()).unwrap_or_default();
+    if let Err(response) = verify_request(&state.signing_secret, &headers, &body) {
+        return response;
+    }
+    match serde_urlencoded::from_str::<SlackCommandEvent>(&body) {
+        Ok(command) => json_response(state.runtime.dispatch_command(command).await),
+        Err(e) => bad_request(e),
+    }
+}
+
+/// HTTPで受信したインタラクションを検証してランタイムに渡します
+async fn handle_http_interaction<H: SlackEventHandler>(
+    State(state): State<HttpState<H>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response<Body> {
+    let body = String::from_utf8(body.to_vec()).unwrap_or_default();
+    if let Err(response) = verify_request(&state.signing_secret, &headers, &body) {
+        return response;
+    }
+    let payload = serde_urlencoded::from_str::<InteractionForm>(&body)
+        .map_err(|e| e.to_string())
+        .and_then(|form| serde_json::from_str(&form.payload).map_err(|e| e.to_string()));
+    let result = match payload {
+        Ok(payload) => state
+            .runtime
+            .dispatch_interaction(payload)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(response) => json_response(response),
+        Err(e) => bad_request(e),
+    }
+}
+
+/// ハンドラの応答をJSONで返します（応答がない場合は空のボディ）
+fn json_response(payload: Option<Value>) -> Response<Body> {
+    let response = Response::builder().status(StatusCode::OK);
+    match payload {
+        Some(payload) => response
+            .header("Content-Type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+        None => response.body(Body::empty()).unwrap(),
+    }
+}
+
+/// パースできないリクエストに400を返します
+fn bad_request(e: impl fmt::Display) -> Response<Body> {
+    error!("リクエストのパースに失敗: {}", e);
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from("Invalid request"))
+        .unwrap()
+}
+
+/// Socket Modeで受信したイベントをランタイムに渡します
+#[cfg(feature = "socket_mode")]
+struct SocketAdapter<H: SlackEventHandler>(Arc<Runtime<H>>);
+
+#[cfg(feature = "socket_mode")]
+#[async_trait]
+impl<H: SlackEventHandler> SocketModeHandler for SocketAdapter<H> {
+    async fn handle(&self, event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>> {
+        debug!("未対応のSocket Modeイベント: {:?}", event);
+        Ok(None)
+    }
+
+    async fn handle_payload(
+        &self,
+        kind: &str,
+        payload: Value,
+    ) -> Result<Option<Value>, Box<dyn Error>> {
+        match kind {
+            // メタデータのイベントなども読み取れるよう、型に変換する前のJSONを渡す
+            "events_api" => {
+                self.0.dispatch(&payload.to_string()).await?;
+                Ok(None)
+            }
+            "slash_commands" => Ok(self
+                .0
+                .dispatch_command(serde_json::from_value(payload)?)
+                .await),
+            "interactive" => Ok(self.0.dispatch_interaction(payload).await?),
+            _ => {
+                debug!("未対応のSocket Modeイベント: {}", kind);
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oauth::{TokenResponse, TokenStorage};
+    use serde_json::json;
+    use tokio::sync::{mpsc, RwLock};
+
+    /// 受信したイベントをテストに渡すハンドラ
+    #[derive(Clone)]
+    struct Recorder(mpsc::UnboundedSender<Event>);
+
+    #[async_trait]
+    impl SlackEventHandler for Recorder {
+        async fn handle_event(
+            &self,
+            event: Event,
+            _client: &MessageClient,
+        ) -> Result<(), Box<dyn Error>> {
+            self.0.send(event)?;
+            Ok(())
+        }
+
+        async fn handle_command(
+            &self,
+            command: SlackCommandEvent,
+            _client: &MessageClient,
+        ) -> Result<Option<Value>, Box<dyn Error>> {
+            Ok(Some(
+                json!({"text": format!("{}を受け付けました", command.command)}),
+            ))
+        }
+
+        async fn handle_interaction(
+            &self,
+            _interaction: SlackInteractionEvent,
+            _client: &MessageClient,
+        ) -> Result<Option<Value>, Box<dyn Error>> {
+            Ok(Some(json!({"response_action": "clear"})))
+        }
+    }
+
+    /// 特定のチャンネルのイベントを破棄するミドルウェア
+    struct IgnoreChannel(&'static str);
+
+    #[async_trait]
+    impl Middleware for IgnoreChannel {
+        async fn before(&self, event: &Event) -> bool {
+            !matches!(event, Event::Message { channel, .. } if channel == self.0)
+        }
+    }
+
+    struct Storage;
+
+    impl TokenStorage for Storage {
+        fn get_token(&self, team_id: &str) -> Option<TokenResponse> {
+            (team_id == "T1").then(|| TokenResponse {
+                access_token: "xoxb-team-1".to_string(),
+            })
+        }
+    }
+
+    fn message(event_id: &str, channel: &str) -> String {
+        json!({
+            "token": "t",
+            "team_id": "T1",
+            "api_app_id": "A1",
+            "event": {
+                "type": "message",
+                "channel": channel,
+                "user": "U1",
+                "text": "こんにちは",
+                "ts": "1700000000.000100"
+            },
+            "type": "event_callback",
+            "event_id": event_id,
+            "event_time": 1700000000
+        })
+        .to_string()
+    }
+
+    fn start(app: SlackApp<Recorder>) -> Arc<Runtime<Recorder>> {
+        app.into_runtime().unwrap().0
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_dedup_and_middleware() {
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let runtime = start(
+            SlackApp::new(Recorder(sender))
+                .with_bot_token(Token::new("xoxb-test"))
+                .with_middleware(IgnoreChannel("C2")),
+        );
+
+        let challenge = json!({"type": "url_verification", "challenge": "abc", "token": "t"});
+        assert_eq!(
+            runtime.dispatch(&challenge.to_string()).await.unwrap(),
+            Some("abc".to_string())
+        );
+
+        runtime.dispatch(&message("Ev1", "C1")).await.unwrap();
+        // 再送されたイベントとミドルウェアが破棄したイベントはハンドラに届かない
+        runtime.dispatch(&message("Ev1", "C1")).await.unwrap();
+        runtime.dispatch(&message("Ev2", "C2")).await.unwrap();
+        runtime.dispatch(&message("Ev3", "C3")).await.unwrap();
+
+        let channel = |event: Event| match event {
+            Event::Message { channel, .. } => channel,
+            _ => panic!("メッセージイベントではありません"),
+        };
+        assert_eq!(channel(events.recv().await.unwrap()), "C1");
+        assert_eq!(channel(events.recv().await.unwrap()), "C3");
+        assert!(events.try_recv().is_err());
+        assert!(runtime.dispatch("{").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolves_tokens_per_team() {
+        let oauth = OAuthConfig {
+            token_storage: Arc::new(RwLock::new(Storage)),
+        };
+        let (sender, _events) = mpsc::unbounded_channel();
+        let (t1, t2) = (TeamId::new("T1").unwrap(), TeamId::new("T2").unwrap());
+        let runtime = start(SlackApp::new(Recorder(sender.clone())).with_oauth(oauth.clone()));
+        assert!(runtime.tokens.client_for(Some(&t1)).await.is_ok());
+        assert_eq!(runtime.tokens.clients.lock().unwrap().len(), 1);
+        assert!(runtime.tokens.client_for(Some(&t2)).await.is_err());
+
+        // ストレージにないチームはボットトークンを使う
+        let runtime = start(
+            SlackApp::new(Recorder(sender))
+                .with_oauth(oauth)
+                .with_bot_token(Token::new("xoxb-test")),
+        );
+        assert!(runtime.tokens.client_for(Some(&t2)).await.is_ok());
+        assert!(runtime.tokens.client_for(None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_http_rejects_unsigned_requests() {
+        use tower::ServiceExt;
+
+        let (sender, _events) = mpsc::unbounded_channel();
+        let router = SlackApp::new(Recorder(sender))
+            .with_signing_secret(SigningSecret::new("secret"))
+            .with_events_path("/slack/events")
+            .into_router();
+        let request = axum::http::Request::post("/slack/events")
+            .body(axum::body::Body::from(message("Ev1", "C1")))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // スラッシュコマンドとインタラクションのパスも署名を検証する
+        for path in [DEFAULT_COMMANDS_PATH, DEFAULT_INTERACTIONS_PATH] {
+            let request = axum::http::Request::post(path)
+                .body(axum::body::Body::from("payload=%7B%7D"))
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[cfg(feature = "config")]
+    #[tokio::test]
+    async fn test_from_config_serves_configured_paths() {
+        use tower::ServiceExt;
+
+        let config = SlackConfig::from_toml_str(
+            r#"
+            bot_token = "xoxb-test"
+            signing_secret = "secret"
+            commands_path = "/slack/commands"
+            interactions_path = "/slack/interactions"
+            "#,
+        )
+        .unwrap();
+        let (sender, _events) = mpsc::unbounded_channel();
+        let router = SlackApp::from_config(Recorder(sender), &config)
+            .try_into_router()
+            .unwrap();
+        for (path, status) in [
+            ("/slack/commands", StatusCode::UNAUTHORIZED),
+            ("/slack/interactions", StatusCode::UNAUTHORIZED),
+            (DEFAULT_COMMANDS_PATH, StatusCode::NOT_FOUND),
+        ] {
+            let request = axum::http::Request::post(path)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_manifest_uses_handler_and_paths() {
+        use crate::SlashCommand;
+
+        #[derive(Clone)]
+        struct Deploy;
+
+        #[async_trait]
+        impl SlackEventHandler for Deploy {
+            async fn handle_event(
+                &self,
+                _event: Event,
+                _client: &MessageClient,
+            ) -> Result<(), Box<dyn Error>> {
+                Ok(())
+            }
+
+            fn manifest(&self, manifest: AppManifest) -> AppManifest {
+                manifest
+                    .with_bot_event("app_mention")
+                    .with_command(SlashCommand::new("/deploy", "デプロイを実行"))
+                    .with_interactivity()
+            }
+        }
+
+        let app = SlackApp::new(Deploy)
+            .with_events_path("/slack/events")
+            .with_commands_path("/slack/commands");
+        let manifest = app.manifest("デプロイBot", Some("https://example.com"));
+        let settings = &manifest.settings;
+        assert_eq!(
+            settings.event_subscriptions.as_ref().unwrap().request_url,
+            Some("https://example.com/slack/events".to_string())
+        );
+        assert_eq!(
+            settings.interactivity.as_ref().unwrap().request_url,
+            Some("https://example.com/interactions".to_string())
+        );
+        assert_eq!(
+            manifest.features.slash_commands[0].url.as_deref(),
+            Some("https://example.com/slack/commands")
+        );
+        assert!(manifest.oauth_config.scopes.bot.contains("commands"));
+        assert!(!settings.socket_mode_enabled);
+
+        let manifest = app.manifest("デプロイBot", None);
+        assert!(manifest.settings.socket_mode_enabled);
+        assert!(manifest.features.slash_commands[0].url.is_none());
+    }
+
+    #[test]
+    fn test_try_into_router_reports_invalid_settings() {
+        let (sender, _events) = mpsc::unbounded_channel();
+        let app = SlackApp::new(Recorder(sender.clone())).with_bot_token(Token::new("xoxb-test"));
+        assert_eq!(
+            app.try_into_router().err(),
+            Some(InvalidApp::MissingSigningSecret)
+        );
+
+        let app = SlackApp::new(Recorder(sender))
+            .with_bot_token(Token::new("xapp-1-test"))
+            .with_signing_secret(SigningSecret::new("secret"));
+        assert!(matches!(
+            app.try_into_router().err(),
+            Some(InvalidApp::BotToken(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_commands_and_interactions() {
+        let (sender, _events) = mpsc::unbounded_channel();
+        let runtime =
+            start(SlackApp::new(Recorder(sender)).with_bot_token(Token::new("xoxb-test")));
+
+        let form = "team_id=T1&channel_id=C1&user_id=U1&command=%2Fdeploy&text=prod\
+                    &response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2F1\
+                    &trigger_id=1.2.3";
+        let command = serde_urlencoded::from_str(form).unwrap();
+        assert_eq!(
+            runtime.dispatch_command(command).await,
+            Some(json!({"text": "/deployを受け付けました"}))
+        );
+
+        let interaction = json!({
+            "type": "shortcut",
+            "callback_id": "open",
+            "trigger_id": "1.2.3",
+            "team": {"id": "T1"},
+            "user": {"id": "U1", "team_id": "T1"},
+            "action_ts": "1700000000.000100"
+        });
+        assert_eq!(
+            runtime.dispatch_interaction(interaction).await.unwrap(),
+            Some(json!({"response_action": "clear"}))
+        );
+        assert!(runtime
+            .dispatch_interaction(json!({"type": "unknown"}))
+            .await
+            .is_err());
+    }
+
+    #[cfg(feature = "socket_mode")]
+    #[tokio::test]
+    async fn test_socket_mode_uses_same_pipeline() {
+        use crate::socket_mode::fake_server::FakeSocketModeServer;
+
+        let mut server = FakeSocketModeServer::start().await;
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let runtime =
+            start(SlackApp::new(Recorder(sender)).with_bot_token(Token::new("xoxb-test")));
+        let handle = server
+            .client()
+            .connect_with(SocketAdapter(runtime))
+            .await
+            .unwrap();
+        let mut connection = server.accept().await;
+
+        let payload: Value = serde_json::from_str(&message("Ev1", "C1")).unwrap();
+        connection
+            .send_event("events_api", "e1", payload.clone())
+            .await;
+        assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e1"}));
+        // 別の接続から再送されても一度しか処理しない
+        connection.send_event("events_api", "e2", payload).await;
+        assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e2"}));
+
+        assert!(matches!(events.recv().await, Some(Event::Message { .. })));
+
+        // スラッシュコマンドの応答はackのペイロードとして返す
+        let command = json!({
+            "team_id": "T1",
+            "channel_id": "C1",
+            "user_id": "U1",
+            "command": "/deploy",
+            "response_url": "https://hooks.slack.com/commands/1",
+            "trigger_id": "1.2.3"
+        });
+        connection.send_event("slash_commands", "e3", command).await;
+        assert_eq!(
+            connection.recv_ack().await,
+            json!({"envelope_id": "e3", "payload": {"text": "/deployを受け付けました"}})
+        );
+        handle.shutdown().await;
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! ブロック内で使用するインタラクティブ要素
+
+use super::TextObject;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// ブロック要素
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockElement {
+    /// ボタン
+    Button(ButtonElement),
+    /// 画像
+    Image(ImageElement),
+    /// 1行または複数行のテキスト入力
+    PlainTextInput(PlainTextInputElement),
+    /// 静的な選択肢のセレクトメニュー
+    StaticSelect(StaticSelectElement),
+    /// 静的な選択肢の複数選択メニュー
+    MultiStaticSelect(MultiStaticSelectElement),
+    /// ユーザー選択メニュー
+    UsersSelect(UsersSelectElement),
+    /// ユーザー複数選択メニュー
+    MultiUsersSelect(MultiUsersSelectElement),
+    /// 会話選択メニュー
+    ConversationsSelect(ConversationsSelectElement),
+    /// 会話複数選択メニュー
+    MultiConversationsSelect(MultiConversationsSelectElement),
+    /// 日付選択
+    DatePicker(DatePickerElement),
+    /// 時刻選択
+    TimePicker(TimePickerElement),
+    /// チェックボックス
+    Checkboxes(CheckboxesElement),
+    /// ラジオボタン
+    RadioButtons(RadioButtonsElement),
+    /// オーバーフローメニュー
+    Overflow(OverflowElement),
+    /// 未対応の要素（受信したJSONをそのまま保持）
+    Unknown(Value),
+}
+
+impl_tagged_serde!(BlockElement {
+    "button" => Button,
+    "image" => Image,
+    "plain_text_input" => PlainTextInput,
+    "static_select" => StaticSelect,
+    "multi_static_select" => MultiStaticSelect,
+    "users_select" => UsersSelect,
+    "multi_users_select" => MultiUsersSelect,
+    "conversations_select" => ConversationsSelect,
+    "multi_conversations_select" => MultiConversationsSelect,
+    "datepicker" => DatePicker,
+    "timepicker" => TimePicker,
+    "checkboxes" => Checkboxes,
+    "radio_buttons" => RadioButtons,
+    "overflow" => Overflow,
+});
+
+/// 選択肢
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionObject {
+    /// 表示テキスト
+    pub text: TextObject,
+    /// 選択時に送信される値
+    pub value: String,
+    /// 補足説明
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<TextObject>,
+    /// オーバーフローメニューで開くURL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl OptionObject {
+    /// プレーンテキストの選択肢を作成
+    pub fn new(text: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            text: TextObject::plain(text),
+            value: value.into(),
+            description: None,
+            url: None,
+        }
+    }
+}
+
+/// ボタン
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ButtonElement {
+    /// ボタンのラベル
+    pub text: TextObject,
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// クリック時に開くURL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// クリック時に送信される値
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// ボタンのスタイル（`primary`または`danger`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// スクリーンリーダー向けのラベル
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessibility_label: Option<String>,
+}
+
+impl ButtonElement {
+    /// ラベルとアクションIDを指定してボタンを作成
+    pub fn new(text: impl Into<String>, action_id: impl Into<String>) -> Self {
+        Self {
+            text: TextObject::plain(text),
+            action_id: Some(action_id.into()),
+            url: None,
+            value: None,
+            style: None,
+            accessibility_label: None,
+        }
+    }
+}
+
+/// 画像
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageElement {
+    /// 画像のURL
+    pub image_url: String,
+    /// 代替テキスト
+    pub alt_text: String,
+}
+
+/// テキスト入力
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlainTextInputElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期値
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_value: Option<String>,
+    /// 複数行入力にするか
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiline: Option<bool>,
+    /// 最小文字数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u32>,
+    /// 最大文字数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u32>,
+}
+
+/// 静的な選択肢のセレクトメニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StaticSelectElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 選択肢
+    pub options: Vec<OptionObject>,
+    /// 初期選択
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_option: Option<OptionObject>,
+}
+
+/// 静的な選択肢の複数選択メニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultiStaticSelectElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 選択肢
+    pub options: Vec<OptionObject>,
+    /// 初期選択
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_options: Option<Vec<OptionObject>>,
+    /// 最大選択数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_selected_items: Option<u32>,
+}
+
+/// ユーザー選択メニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsersSelectElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期選択のユーザーID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_user: Option<String>,
+}
+
+/// ユーザー複数選択メニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultiUsersSelectElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期選択のユーザーID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_users: Option<Vec<String>>,
+    /// 最大選択数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_selected_items: Option<u32>,
+}
+
+/// 会話選択メニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConversationsSelectElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期選択の会話ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_conversation: Option<String>,
+    /// 現在の会話を初期選択にするか
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_to_current_conversation: Option<bool>,
+}
+
+/// 会話複数選択メニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultiConversationsSelectElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期選択の会話ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_conversations: Option<Vec<String>>,
+    /// 最大選択数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_selected_items: Option<u32>,
+}
+
+/// 日付選択
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatePickerElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期値（`YYYY-MM-DD`形式）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_date: Option<String>,
+}
+
+/// 時刻選択
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimePickerElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// プレースホルダー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<TextObject>,
+    /// 初期値（`HH:mm`形式）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_time: Option<String>,
+    /// タイムゾーン（IANA形式）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+/// チェックボックス
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckboxesElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// 選択肢
+    pub options: Vec<OptionObject>,
+    /// 初期選択
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_options: Option<Vec<OptionObject>>,
+}
+
+/// ラジオボタン
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RadioButtonsElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// 選択肢
+    pub options: Vec<OptionObject>,
+    /// 初期選択
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_option: Option<OptionObject>,
+}
+
+/// オーバーフローメニュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverflowElement {
+    /// アクションID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// 選択肢
+    pub options: Vec<OptionObject>,
+}
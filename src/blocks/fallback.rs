@@ -0,0 +1,208 @@
+//! ブロックから通知やスクリーンリーダー向けのプレーンテキストを生成する
+
+use super::{
+    Block, BlockElement, ContextElement, RichTextElement, RichTextInline, RichTextSection,
+    TextObject,
+};
+
+/// ブロックの並びから、メッセージの`text`に設定する代替テキストを生成します。
+///
+/// ヘッダー、セクション（フィールドを含む）、コンテキスト、リッチテキスト、
+/// ボタンのラベルを読みやすい順に改行で連結します。
+/// 区切り線や表示内容を持たないブロックは無視されます。
+pub fn fallback_text(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .filter_map(render_block)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_block(block: &Block) -> Option<String> {
+    match block {
+        Block::Header(header) => Some(header.text.text().to_string()),
+        Block::Section(section) => {
+            let mut lines = Vec::new();
+            if let Some(text) = &section.text {
+                lines.push(text.text().to_string());
+            }
+            if let Some(fields) = &section.fields {
+                lines.extend(fields.iter().map(|f| f.text().to_string()));
+            }
+            if let Some(label) = section.accessory.as_ref().and_then(element_label) {
+                lines.push(label);
+            }
+            Some(lines.join("\n"))
+        }
+        Block::Context(context) => Some(
+            context
+                .elements
+                .iter()
+                .filter_map(|element| match element {
+                    ContextElement::Text(text) => Some(text.text()),
+                    ContextElement::Element(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Block::Actions(actions) => Some(
+            actions
+                .elements
+                .iter()
+                .filter_map(element_label)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Block::Image(image) => Some(
+            image
+                .title
+                .as_ref()
+                .map(TextObject::text)
+                .unwrap_or(&image.alt_text)
+                .to_string(),
+        ),
+        Block::RichText(rich_text) => Some(
+            rich_text
+                .elements
+                .iter()
+                .map(|element| render_rich_text_element(element, 0))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        Block::Divider(_) | Block::Input(_) | Block::Unknown(_) => None,
+    }
+}
+
+fn element_label(element: &BlockElement) -> Option<String> {
+    match element {
+        BlockElement::Button(button) => Some(format!("[{}]", button.text.text())),
+        _ => None,
+    }
+}
+
+fn render_rich_text_element(element: &RichTextElement, depth: usize) -> String {
+    match element {
+        RichTextElement::Section(section) => render_inlines(section),
+        RichTextElement::Preformatted(section) => format!("```{}```", render_inlines(section)),
+        RichTextElement::Quote(section) => render_inlines(section)
+            .lines()
+            .map(|line| format!("> {}", line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        RichTextElement::List(list) => {
+            let indent = list.indent.unwrap_or(0) as usize + depth;
+            let start = list.offset.unwrap_or(0) as usize;
+            list.elements
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let marker = if list.style == "ordered" {
+                        format!("{}.", start + i + 1)
+                    } else {
+                        "•".to_string()
+                    };
+                    format!(
+                        "{}{} {}",
+                        "    ".repeat(indent),
+                        marker,
+                        render_rich_text_element(item, depth + 1)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        RichTextElement::Unknown(_) => String::new(),
+    }
+}
+
+fn render_inlines(section: &RichTextSection) -> String {
+    section
+        .elements
+        .iter()
+        .map(|inline| match inline {
+            RichTextInline::Text(text) => text.text.clone(),
+            RichTextInline::Link(link) => link.text.clone().unwrap_or_else(|| link.url.clone()),
+            RichTextInline::User(user) => format!("<@{}>", user.user_id),
+            RichTextInline::Channel(channel) => format!("<#{}>", channel.channel_id),
+            RichTextInline::Usergroup(group) => format!("<!subteam^{}>", group.usergroup_id),
+            RichTextInline::Emoji(emoji) => format!(":{}:", emoji.name),
+            RichTextInline::Broadcast(broadcast) => format!("<!{}>", broadcast.range),
+            RichTextInline::Date(date) => date
+                .fallback
+                .clone()
+                .unwrap_or_else(|| date.timestamp.to_string()),
+            RichTextInline::Unknown(_) => String::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{ActionsBlock, ButtonElement, SectionBlock};
+
+    #[test]
+    fn test_fallback_text_from_sections_and_buttons() {
+        let blocks = vec![
+            Block::header("デプロイ完了"),
+            Block::divider(),
+            Block::Section(SectionBlock {
+                text: Some(TextObject::mrkdwn("*production* に反映しました")),
+                fields: Some(vec![
+                    TextObject::mrkdwn("*環境:* production"),
+                    TextObject::mrkdwn("*担当:* <@U012AB3CD>"),
+                ]),
+                ..Default::default()
+            }),
+            Block::Actions(ActionsBlock {
+                block_id: None,
+                elements: vec![
+                    BlockElement::Button(ButtonElement::new("承認", "approve")),
+                    BlockElement::Button(ButtonElement::new("却下", "reject")),
+                ],
+            }),
+        ];
+
+        assert_eq!(
+            fallback_text(&blocks),
+            "デプロイ完了\n*production* に反映しました\n*環境:* production\n*担当:* <@U012AB3CD>\n[承認] [却下]"
+        );
+    }
+
+    #[test]
+    fn test_fallback_text_from_rich_text() {
+        let block: Block = serde_json::from_value(serde_json::json!({
+            "type": "rich_text",
+            "elements": [
+                {"type": "rich_text_section", "elements": [
+                    {"type": "text", "text": "確認: "},
+                    {"type": "user", "user_id": "U1"},
+                    {"type": "emoji", "name": "eyes"}
+                ]},
+                {"type": "rich_text_list", "style": "ordered", "elements": [
+                    {"type": "rich_text_section", "elements": [{"type": "text", "text": "ビルド"}]},
+                    {"type": "rich_text_section", "elements": [
+                        {"type": "link", "url": "https://example.com"}
+                    ]}
+                ]},
+                {"type": "rich_text_quote", "elements": [{"type": "broadcast", "range": "here"}]}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            fallback_text(&[block]),
+            "確認: <@U1>:eyes:\n1. ビルド\n2. https://example.com\n> <!here>"
+        );
+    }
+
+    #[test]
+    fn test_fallback_text_ignores_unknown_blocks() {
+        let blocks = vec![
+            Block::divider(),
+            Block::Unknown(serde_json::json!({"type": "video"})),
+        ];
+        assert_eq!(fallback_text(&blocks), "");
+    }
+}
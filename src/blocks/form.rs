@@ -0,0 +1,436 @@
+//! 送信されたビューの入力値を構造体に変換する
+
+use super::{StateValue, ViewState};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, error::Error, fmt, str::FromStr};
+
+const REQUIRED_MESSAGE: &str = "この項目は必須です";
+
+/// ビューの入力値から変換できる型
+///
+/// 通常は[`impl_from_view_state!`](crate::impl_from_view_state)で実装します。
+pub trait FromViewState: Sized {
+    /// 入力値を変換します。入力に誤りがある場合はブロックごとのエラーを返します
+    fn from_view_state(state: &ViewState) -> Result<Self, ViewStateErrors>;
+}
+
+/// 1つの入力要素の値から変換できる型
+pub trait FromStateValue: Sized {
+    /// 値を変換します。`value`は未送信の場合`None`です
+    ///
+    /// # 戻り値
+    /// 変換できない場合はユーザーに表示するエラーメッセージ
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String>;
+}
+
+/// 入力が必須の単一の値
+fn required(value: Option<&StateValue>) -> Result<&str, String> {
+    value
+        .and_then(StateValue::as_str)
+        .ok_or_else(|| REQUIRED_MESSAGE.to_string())
+}
+
+impl FromStateValue for String {
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        required(value).map(str::to_string)
+    }
+}
+
+impl FromStateValue for Vec<String> {
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        Ok(value
+            .map(|v| v.as_list().into_iter().map(str::to_string).collect())
+            .unwrap_or_default())
+    }
+}
+
+impl<T: FromStateValue> FromStateValue for Option<T> {
+    /// 未入力・未選択の場合は`None`（複数選択では1つも選択されていない場合）
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        match value {
+            Some(v) if !v.as_list().is_empty() || v.selected_date_time.is_some() => {
+                T::from_state_value(Some(v)).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+impl FromStateValue for i64 {
+    /// 数値入力の値、または日時選択のUNIX時間
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        if let Some(timestamp) = value.and_then(|v| v.selected_date_time) {
+            return Ok(timestamp);
+        }
+        required(value)?
+            .parse()
+            .map_err(|_| "整数を入力してください".to_string())
+    }
+}
+
+impl FromStateValue for f64 {
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        required(value)?
+            .parse()
+            .map_err(|_| "数値を入力してください".to_string())
+    }
+}
+
+/// 日付選択（`datepicker`）の値
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// `YYYY-MM-DD`形式の日付を解析します
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || "日付の形式が正しくありません".to_string();
+        let mut parts = s.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        let date = Self {
+            year: year.parse().map_err(|_| invalid())?,
+            month: month.parse().map_err(|_| invalid())?,
+            day: day.parse().map_err(|_| invalid())?,
+        };
+        if !(1..=12).contains(&date.month) || !(1..=31).contains(&date.day) {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStateValue for Date {
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        required(value)?.parse()
+    }
+}
+
+/// 時刻選択（`timepicker`）の値
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl FromStr for Time {
+    type Err = String;
+
+    /// `HH:mm`形式の時刻を解析します
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || "時刻の形式が正しくありません".to_string();
+        let (hour, minute) = s.split_once(':').ok_or_else(invalid)?;
+        let time = Self {
+            hour: hour.parse().map_err(|_| invalid())?,
+            minute: minute.parse().map_err(|_| invalid())?,
+        };
+        if time.hour > 23 || time.minute > 59 {
+            return Err(invalid());
+        }
+        Ok(time)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl FromStateValue for Time {
+    fn from_state_value(value: Option<&StateValue>) -> Result<Self, String> {
+        required(value)?.parse()
+    }
+}
+
+/// 入力値のエラー（ブロックIDごとのメッセージ）
+///
+/// [`to_response`](Self::to_response)の結果を`view_submission`の応答として返すと、
+/// モーダルの各入力ブロックの下にメッセージが表示されます。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewStateErrors {
+    pub errors: BTreeMap<String, String>,
+}
+
+impl ViewStateErrors {
+    /// ブロックにエラーを追加します（同じブロックに既にエラーがある場合は最初のものを残します）
+    pub fn insert(&mut self, block_id: impl Into<String>, message: impl Into<String>) {
+        self.errors
+            .entry(block_id.into())
+            .or_insert_with(|| message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// `view_submission`に対する`response_action: errors`の応答
+    pub fn to_response(&self) -> Value {
+        json!({ "response_action": "errors", "errors": self.errors })
+    }
+}
+
+impl fmt::Display for ViewStateErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self
+            .errors
+            .iter()
+            .map(|(block_id, message)| format!("{}: {}", block_id, message))
+            .collect();
+        write!(f, "入力値に誤りがあります（{}）", messages.join(", "))
+    }
+}
+
+impl Error for ViewStateErrors {}
+
+/// 入力値を1項目ずつ読み取り、エラーをまとめて返すためのヘルパー
+///
+/// ```
+/// use slack_rs::blocks::{Date, FromViewState, ViewState, ViewStateErrors, ViewStateReader};
+///
+/// struct Incident {
+///     title: String,
+///     due: Option<Date>,
+/// }
+///
+/// impl FromViewState for Incident {
+///     fn from_view_state(state: &ViewState) -> Result<Self, ViewStateErrors> {
+///         let mut reader = ViewStateReader::new(state);
+///         let incident = Incident {
+///             title: reader.field("title", "title_input"),
+///             due: reader.field("due", "due_date"),
+///         };
+///         if incident.title.len() > 50 {
+///             reader.error("title", "50文字以内で入力してください");
+///         }
+///         reader.finish(incident)
+///     }
+/// }
+/// ```
+pub struct ViewStateReader<'a> {
+    state: &'a ViewState,
+    errors: ViewStateErrors,
+}
+
+impl<'a> ViewStateReader<'a> {
+    pub fn new(state: &'a ViewState) -> Self {
+        Self {
+            state,
+            errors: ViewStateErrors::default(),
+        }
+    }
+
+    /// 値を読み取ります
+    ///
+    /// 変換できない場合はエラーを記録し、`T::default()`を返します。
+    pub fn field<T>(&mut self, block_id: &str, action_id: &str) -> T
+    where
+        T: FromStateValue + Default,
+    {
+        match T::from_state_value(self.state.get(block_id, action_id)) {
+            Ok(value) => value,
+            Err(message) => {
+                self.errors.insert(block_id, message);
+                T::default()
+            }
+        }
+    }
+
+    /// 独自の検証エラーを追加します
+    pub fn error(&mut self, block_id: &str, message: impl Into<String>) {
+        self.errors.insert(block_id, message);
+    }
+
+    /// エラーがなければ`value`を返します
+    pub fn finish<T>(self, value: T) -> Result<T, ViewStateErrors> {
+        if self.errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// 構造体のフィールドとブロックID・アクションIDを対応付けて[`FromViewState`]を実装します
+///
+/// 各フィールドの型は[`FromStateValue`]と`Default`を実装している必要があります
+/// （`String`、`Option<T>`、`Vec<String>`、`i64`、`f64`、[`Date`]、[`Time`]など）。
+///
+/// ```
+/// use slack_rs::blocks::{Date, FromViewState, ViewState};
+///
+/// struct Incident {
+///     title: String,
+///     due: Option<Date>,
+///     responders: Vec<String>,
+/// }
+///
+/// slack_rs::impl_from_view_state!(Incident {
+///     title => ("title", "title_input"),
+///     due => ("due", "due_date"),
+///     responders => ("responders", "responders_select"),
+/// });
+///
+/// let state: ViewState = serde_json::from_value(serde_json::json!({
+///     "values": {
+///         "title": {"title_input": {"type": "plain_text_input", "value": null}},
+///         "due": {"due_date": {"type": "datepicker", "selected_date": "2024-04-01"}},
+///         "responders": {"responders_select": {"type": "multi_users_select", "selected_users": ["U1"]}}
+///     }
+/// })).unwrap();
+/// let errors = Incident::from_view_state(&state).err().unwrap();
+/// assert_eq!(errors.errors["title"], "この項目は必須です");
+/// ```
+#[macro_export]
+macro_rules! impl_from_view_state {
+    ($ty:ident { $($field:ident => ($block_id:expr, $action_id:expr)),* $(,)? }) => {
+        impl $crate::blocks::FromViewState for $ty {
+            fn from_view_state(
+                state: &$crate::blocks::ViewState,
+            ) -> Result<Self, $crate::blocks::ViewStateErrors> {
+                let mut reader = $crate::blocks::ViewStateReader::new(state);
+                let value = Self {
+                    $($field: reader.field($block_id, $action_id),)*
+                };
+                reader.finish(value)
+            }
+        }
+    };
+}
+
+impl ViewState {
+    /// 入力値を[`FromViewState`]を実装した型に変換します
+    pub fn read<T: FromViewState>(&self) -> Result<T, ViewStateErrors> {
+        T::from_view_state(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Incident {
+        title: String,
+        severity: Option<String>,
+        due: Date,
+        start: Option<Time>,
+        responders: Vec<String>,
+        budget: Option<f64>,
+    }
+
+    impl_from_view_state!(Incident {
+        title => ("title", "title_input"),
+        severity => ("severity", "severity_select"),
+        due => ("due", "due_date"),
+        start => ("start", "start_time"),
+        responders => ("responders", "responders_select"),
+        budget => ("budget", "budget_input"),
+    });
+
+    #[test]
+    fn test_read_view_state() {
+        let state: ViewState = serde_json::from_value(json!({
+            "values": {
+                "title": {"title_input": {"type": "plain_text_input", "value": "決済APIの障害"}},
+                "severity": {"severity_select": {"type": "static_select", "selected_option": null}},
+                "due": {"due_date": {"type": "datepicker", "selected_date": "2024-04-01"}},
+                "start": {"start_time": {"type": "timepicker", "selected_time": "09:30"}},
+                "responders": {"responders_select": {
+                    "type": "multi_users_select", "selected_users": ["U1", "U2"]
+                }}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            state.read::<Incident>().unwrap(),
+            Incident {
+                title: "決済APIの障害".to_string(),
+                severity: None,
+                due: Date {
+                    year: 2024,
+                    month: 4,
+                    day: 1
+                },
+                start: Some(Time {
+                    hour: 9,
+                    minute: 30
+                }),
+                responders: vec!["U1".to_string(), "U2".to_string()],
+                budget: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_optional_multi_select() {
+        #[derive(Debug, PartialEq)]
+        struct Tags {
+            labels: Option<Vec<String>>,
+            watchers: Option<Vec<String>>,
+        }
+
+        impl_from_view_state!(Tags {
+            labels => ("labels", "labels_select"),
+            watchers => ("watchers", "watchers_select"),
+        });
+
+        let state: ViewState = serde_json::from_value(json!({
+            "values": {
+                "labels": {"labels_select": {
+                    "type": "multi_static_select",
+                    "selected_options": [
+                        {"text": {"type": "plain_text", "text": "バグ"}, "value": "bug"},
+                        {"text": {"type": "plain_text", "text": "緊急"}, "value": "urgent"}
+                    ]
+                }},
+                "watchers": {"watchers_select": {"type": "multi_users_select", "selected_users": []}}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            state.read::<Tags>().unwrap(),
+            Tags {
+                labels: Some(vec!["bug".to_string(), "urgent".to_string()]),
+                watchers: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_field_errors_response() {
+        let state: ViewState = serde_json::from_value(json!({
+            "values": {
+                "due": {"due_date": {"type": "datepicker", "selected_date": "2024-13-01"}},
+                "budget": {"budget_input": {"type": "number_input", "value": "abc"}}
+            }
+        }))
+        .unwrap();
+
+        let errors = state.read::<Incident>().unwrap_err();
+        assert_eq!(
+            errors.to_response(),
+            json!({
+                "response_action": "errors",
+                "errors": {
+                    "budget": "数値を入力してください",
+                    "due": "日付の形式が正しくありません",
+                    "title": "この項目は必須です"
+                }
+            })
+        );
+    }
+}
@@ -1,61 +1,456 @@
 //! Slackメッセージのブロック要素を定義するモジュール
+//!
+//! 各型はSlackのBlock Kit JSON（Block Kit Builderの出力やイベントで受信する`blocks`）と
+//! 同じ形式でシリアライズ・デシリアライズできます。
+//! 未対応のブロック・要素タイプや、既知のタイプでも型で表現できないフィールド
+//! （`slack_file`を指定した画像、`option_groups`を使ったセレクトメニューなど）を含むものは
+//! [`Block::Unknown`]などに生のJSONのまま保持されるため、
+//! 受信したブロックをそのまま送り返しても情報が失われません。
 
-use slack_morphism::blocks::{
-    SlackBlock as MorphismBlock, SlackBlockText, SlackDividerBlock, SlackSectionBlock,
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use slack_morphism::blocks::SlackBlock as MorphismBlock;
+
+/// `type`フィールドで種類を判別し、未知の種類は`Unknown(Value)`に保持する列挙型の
+/// `Serialize`/`Deserialize`を実装します。
+///
+/// 既知の種類でも、型に変換できない場合や、シリアライズし直すと元のJSONと一致しない
+/// （型にないフィールドを含む）場合は`Unknown(Value)`に保持します。
+macro_rules! impl_tagged_serde {
+    ($ty:ident { $($tag:literal => $variant:ident),* $(,)? }) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $($ty::$variant(inner) => $crate::blocks::Tagged { kind: $tag, inner }
+                        .serialize(serializer),)*
+                    $ty::Unknown(value) => value.serialize(serializer),
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = serde_json::Value::deserialize(deserializer)?;
+                let parsed = match value.get("type").and_then(serde_json::Value::as_str) {
+                    $(Some($tag) => serde_json::from_value(value.clone()).ok().map($ty::$variant),)*
+                    _ => None,
+                };
+                match parsed {
+                    Some(parsed) if serde_json::to_value(&parsed).ok().as_ref() == Some(&value) => {
+                        Ok(parsed)
+                    }
+                    _ => Ok($ty::Unknown(value)),
+                }
+            }
+        }
+    };
+}
+
+mod elements;
+mod fallback;
+mod form;
+mod rich_text;
+mod view;
+
+pub use elements::{
+    BlockElement, ButtonElement, CheckboxesElement, ConversationsSelectElement, DatePickerElement,
+    ImageElement, MultiConversationsSelectElement, MultiStaticSelectElement,
+    MultiUsersSelectElement, OptionObject, OverflowElement, PlainTextInputElement,
+    RadioButtonsElement, StaticSelectElement, TimePickerElement, UsersSelectElement,
+};
+pub use fallback::fallback_text;
+pub use form::{Date, FromStateValue, FromViewState, Time, ViewStateErrors, ViewStateReader};
+pub use rich_text::{
+    RichTextBlock, RichTextBroadcast, RichTextChannel, RichTextDate, RichTextElement,
+    RichTextEmoji, RichTextInline, RichTextLink, RichTextList, RichTextSection, RichTextStyle,
+    RichTextText, RichTextUser, RichTextUsergroup,
+};
+pub use view::{
+    InvalidView, StateValue, View, ViewKind, ViewState, MAX_PRIVATE_METADATA_LENGTH,
+    MAX_VIEW_BLOCKS, MAX_VIEW_TITLE_LENGTH,
 };
 
+/// セクションブロックのテキストの最大文字数
+pub const MAX_SECTION_TEXT_LENGTH: usize = 3000;
+
+/// セクションブロックのフィールドの最大数
+pub const MAX_SECTION_FIELDS: usize = 10;
+
+/// ヘッダーブロックのテキストの最大文字数
+pub const MAX_HEADER_TEXT_LENGTH: usize = 150;
+
+/// シリアライズ時に`type`フィールドを付与するためのラッパー
+#[derive(Serialize)]
+struct Tagged<'a, T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(flatten)]
+    inner: &'a T,
+}
+
 /// Slackメッセージのブロック要素
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Block {
     /// セクションブロック
-    Section {
-        /// セクションのテキスト
+    Section(SectionBlock),
+    /// 区切り線
+    Divider(DividerBlock),
+    /// ヘッダーブロック
+    Header(HeaderBlock),
+    /// 画像ブロック
+    Image(ImageBlock),
+    /// アクションブロック
+    Actions(ActionsBlock),
+    /// コンテキストブロック
+    Context(ContextBlock),
+    /// 入力ブロック（モーダルなどで使用）
+    Input(InputBlock),
+    /// リッチテキストブロック
+    RichText(RichTextBlock),
+    /// 未対応のブロック（受信したJSONをそのまま保持）
+    Unknown(Value),
+}
+
+impl_tagged_serde!(Block {
+    "section" => Section,
+    "divider" => Divider,
+    "header" => Header,
+    "image" => Image,
+    "actions" => Actions,
+    "context" => Context,
+    "input" => Input,
+    "rich_text" => RichText,
+});
+
+impl Block {
+    /// テキストのみのセクションブロックを作成
+    pub fn section(text: TextObject) -> Self {
+        Self::Section(SectionBlock {
+            text: Some(text),
+            ..Default::default()
+        })
+    }
+
+    /// 区切り線を作成
+    pub fn divider() -> Self {
+        Self::Divider(DividerBlock::default())
+    }
+
+    /// ヘッダーブロックを作成
+    pub fn header(text: impl Into<String>) -> Self {
+        Self::Header(HeaderBlock {
+            block_id: None,
+            text: TextObject::plain(text),
+        })
+    }
+
+    /// ブロックのID（未設定の場合は`None`）
+    pub fn block_id(&self) -> Option<&str> {
+        match self {
+            Self::Section(b) => b.block_id.as_deref(),
+            Self::Divider(b) => b.block_id.as_deref(),
+            Self::Header(b) => b.block_id.as_deref(),
+            Self::Image(b) => b.block_id.as_deref(),
+            Self::Actions(b) => b.block_id.as_deref(),
+            Self::Context(b) => b.block_id.as_deref(),
+            Self::Input(b) => b.block_id.as_deref(),
+            Self::RichText(b) => b.block_id.as_deref(),
+            Self::Unknown(value) => value.get("block_id").and_then(Value::as_str),
+        }
+    }
+}
+
+/// テキストオブジェクト
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TextObject {
+    /// プレーンテキスト
+    #[serde(rename = "plain_text")]
+    Plain {
+        /// テキスト
         text: String,
+        /// 絵文字コード（`:smile:`など）を絵文字に変換するか
+        #[serde(skip_serializing_if = "Option::is_none")]
+        emoji: Option<bool>,
+    },
+    /// mrkdwn形式のテキスト
+    #[serde(rename = "mrkdwn")]
+    Mrkdwn {
+        /// テキスト
+        text: String,
+        /// URLやメンションの自動変換を無効にするか
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verbatim: Option<bool>,
     },
-    /// 区切り線
-    Divider,
 }
 
-impl From<Block> for MorphismBlock {
-    fn from(block: Block) -> Self {
-        match block {
-            Block::Section { text } => SlackSectionBlock::new()
-                .with_text(SlackBlockText::Plain(text.into()))
-                .into(),
-            Block::Divider => SlackDividerBlock::new().into(),
+impl TextObject {
+    /// プレーンテキストを作成
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::Plain {
+            text: text.into(),
+            emoji: None,
         }
     }
+
+    /// mrkdwn形式のテキストを作成
+    pub fn mrkdwn(text: impl Into<String>) -> Self {
+        Self::Mrkdwn {
+            text: text.into(),
+            verbatim: None,
+        }
+    }
+
+    /// テキスト本体
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Plain { text, .. } | Self::Mrkdwn { text, .. } => text,
+        }
+    }
+}
+
+/// セクションブロック
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SectionBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// セクションのテキスト
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextObject>,
+    /// 2列で表示されるフィールド
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<TextObject>>,
+    /// 右側に表示される要素
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessory: Option<BlockElement>,
+}
+
+/// 区切り線
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DividerBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+}
+
+/// ヘッダーブロック
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeaderBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// ヘッダーのテキスト（プレーンテキストのみ）
+    pub text: TextObject,
+}
+
+/// 画像ブロック
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// 画像のURL
+    pub image_url: String,
+    /// 代替テキスト
+    pub alt_text: String,
+    /// 画像のタイトル
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<TextObject>,
+}
+
+/// アクションブロック
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionsBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// インタラクティブ要素
+    pub elements: Vec<BlockElement>,
+}
+
+/// コンテキストブロック
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// テキストまたは画像要素
+    pub elements: Vec<ContextElement>,
+}
+
+/// コンテキストブロックの要素
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ContextElement {
+    /// テキスト
+    Text(TextObject),
+    /// 画像などのブロック要素
+    Element(BlockElement),
+}
+
+/// 入力ブロック
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// ラベル
+    pub label: TextObject,
+    /// 入力要素
+    pub element: BlockElement,
+    /// 入力欄の下に表示されるヒント
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<TextObject>,
+    /// 入力を任意にするか
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optional: Option<bool>,
+    /// 入力時に`block_actions`を送信するか
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dispatch_action: Option<bool>,
+}
+
+/// slack-morphismが表現できないブロック（[`Block::Unknown`]の多く）はエラーになります
+///
+/// [`MessageClient`](crate::MessageClient)はブロックをJSONのまま送信するため、この変換は使用しません。
+impl TryFrom<Block> for MorphismBlock {
+    type Error = serde_json::Error;
+
+    fn try_from(block: Block) -> Result<Self, Self::Error> {
+        serde_json::from_value(serde_json::to_value(&block)?)
+    }
+}
+
+/// このクレートの型で表現できないブロックは[`Block::Unknown`]になります
+impl TryFrom<MorphismBlock> for Block {
+    type Error = serde_json::Error;
+
+    fn try_from(block: MorphismBlock) -> Result<Self, Self::Error> {
+        let value = serde_json::to_value(&block)?;
+        Ok(serde_json::from_value(value.clone()).unwrap_or(Self::Unknown(value)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Block Kit Builderで作成したメッセージのJSON
+    const BLOCK_KIT_BUILDER_JSON: &str = include_str!("testdata/block_kit_builder.json");
+
     #[test]
     fn test_section_block_conversion() {
-        let block = Block::Section {
-            text: "テストメッセージ".to_string(),
-        };
-        let _morphism_block: MorphismBlock = block.into();
+        let block = Block::section(TextObject::plain("テストメッセージ"));
+        let _morphism_block: MorphismBlock = block.try_into().unwrap();
     }
 
     #[test]
     fn test_divider_block_conversion() {
-        let block = Block::Divider;
-        let _morphism_block: MorphismBlock = block.into();
+        let block = Block::divider();
+        let _morphism_block: MorphismBlock = block.try_into().unwrap();
     }
 
     #[test]
     fn test_block_list_conversion() {
         let blocks = vec![
-            Block::Section {
-                text: "テスト1".to_string(),
+            Block::section(TextObject::plain("テスト1")),
+            Block::divider(),
+            Block::section(TextObject::plain("テスト2")),
+        ];
+        let _morphism_blocks: Vec<MorphismBlock> = blocks
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_block_kit_builder_round_trip() {
+        let original: Value = serde_json::from_str(BLOCK_KIT_BUILDER_JSON).unwrap();
+        let blocks: Vec<Block> = serde_json::from_value(original["blocks"].clone()).unwrap();
+
+        assert!(matches!(blocks[0], Block::Header(_)));
+        assert!(matches!(blocks[1], Block::Section(_)));
+        assert!(blocks.iter().all(|b| !matches!(b, Block::Unknown(_))));
+
+        let serialized = serde_json::to_value(&blocks).unwrap();
+        assert_eq!(serialized, original["blocks"]);
+    }
+
+    #[test]
+    fn test_unknown_block_round_trip() {
+        let json = serde_json::json!({
+            "type": "video",
+            "block_id": "v1",
+            "title": {"type": "plain_text", "text": "デモ"},
+            "video_url": "https://example.com/demo.mp4",
+            "alt_text": "デモ動画"
+        });
+        let block: Block = serde_json::from_value(json.clone()).unwrap();
+        assert!(matches!(block, Block::Unknown(_)));
+        assert_eq!(block.block_id(), Some("v1"));
+        assert_eq!(serde_json::to_value(&block).unwrap(), json);
+    }
+
+    #[test]
+    fn test_unknown_element_round_trip() {
+        let json = serde_json::json!({
+            "type": "actions",
+            "elements": [
+                {"type": "workflow_button", "text": {"type": "plain_text", "text": "実行"}}
+            ]
+        });
+        let block: Block = serde_json::from_value(json.clone()).unwrap();
+        let Block::Actions(actions) = &block else {
+            panic!("アクションブロックではありません");
+        };
+        assert!(matches!(actions.elements[0], BlockElement::Unknown(_)));
+        assert_eq!(serde_json::to_value(&block).unwrap(), json);
+    }
+
+    #[test]
+    fn test_morphism_round_trip() {
+        let original: Value = serde_json::from_str(BLOCK_KIT_BUILDER_JSON).unwrap();
+        let blocks: Vec<Block> = serde_json::from_value(original["blocks"].clone()).unwrap();
+        for block in blocks {
+            let morphism: MorphismBlock = block.clone().try_into().unwrap();
+            assert_eq!(Block::try_from(morphism).unwrap(), block);
+        }
+    }
+
+    #[test]
+    fn test_unrepresentable_known_types_fall_back_to_unknown() {
+        let json = serde_json::json!([
+            {
+                "type": "image",
+                "slack_file": {"id": "F0123ABCD"},
+                "alt_text": "アップロードした画像"
             },
-            Block::Divider,
-            Block::Section {
-                text: "テスト2".to_string(),
+            {
+                "type": "section",
+                "text": {"type": "mrkdwn", "text": "選択してください"},
+                "accessory": {
+                    "type": "static_select",
+                    "action_id": "choice",
+                    "option_groups": [{
+                        "label": {"type": "plain_text", "text": "グループ"},
+                        "options": [{"text": {"type": "plain_text", "text": "A"}, "value": "a"}]
+                    }]
+                }
             },
-        ];
-        let _morphism_blocks: Vec<MorphismBlock> = blocks.into_iter().map(Into::into).collect();
+            {"type": "divider", "block_id": "d1", "expand": true}
+        ]);
+        let blocks: Vec<Block> = serde_json::from_value(json.clone()).unwrap();
+        assert!(matches!(blocks[0], Block::Unknown(_)));
+        let Block::Section(section) = &blocks[1] else {
+            panic!("セクションブロックではありません");
+        };
+        assert!(matches!(section.accessory, Some(BlockElement::Unknown(_))));
+        // 型にないフィールドを含むブロックも失わずに保持する
+        assert!(matches!(blocks[2], Block::Unknown(_)));
+        assert_eq!(blocks[2].block_id(), Some("d1"));
+        assert_eq!(serde_json::to_value(&blocks).unwrap(), json);
+
+        assert!(MorphismBlock::try_from(blocks[0].clone()).is_err());
     }
 }
@@ -0,0 +1,200 @@
+//! リッチテキストブロック
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// リッチテキストブロック
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RichTextBlock {
+    /// ブロックID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// セクション・リスト・整形済みテキスト・引用
+    pub elements: Vec<RichTextElement>,
+}
+
+/// リッチテキストブロック直下の要素
+#[derive(Debug, Clone, PartialEq)]
+pub enum RichTextElement {
+    /// 段落
+    Section(RichTextSection),
+    /// 箇条書き・番号付きリスト
+    List(RichTextList),
+    /// 整形済みテキスト（コードブロック）
+    Preformatted(RichTextSection),
+    /// 引用
+    Quote(RichTextSection),
+    /// 未対応の要素（受信したJSONをそのまま保持）
+    Unknown(Value),
+}
+
+impl_tagged_serde!(RichTextElement {
+    "rich_text_section" => Section,
+    "rich_text_list" => List,
+    "rich_text_preformatted" => Preformatted,
+    "rich_text_quote" => Quote,
+});
+
+/// インライン要素の並び
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RichTextSection {
+    /// インライン要素
+    pub elements: Vec<RichTextInline>,
+    /// 左側の罫線の太さ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<u32>,
+}
+
+/// リスト
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextList {
+    /// リストのスタイル（`bullet`または`ordered`）
+    pub style: String,
+    /// 各項目（通常は`rich_text_section`）
+    pub elements: Vec<RichTextElement>,
+    /// インデントの深さ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indent: Option<u32>,
+    /// 番号付きリストの開始番号のオフセット
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    /// 左側の罫線の太さ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<u32>,
+}
+
+/// テキストの装飾
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RichTextStyle {
+    /// 太字
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    /// 斜体
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    /// 取り消し線
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strike: Option<bool>,
+    /// インラインコード
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<bool>,
+}
+
+/// リッチテキストのインライン要素
+#[derive(Debug, Clone, PartialEq)]
+pub enum RichTextInline {
+    /// テキスト
+    Text(RichTextText),
+    /// リンク
+    Link(RichTextLink),
+    /// ユーザーメンション
+    User(RichTextUser),
+    /// チャンネルリンク
+    Channel(RichTextChannel),
+    /// ユーザーグループメンション
+    Usergroup(RichTextUsergroup),
+    /// 絵文字
+    Emoji(RichTextEmoji),
+    /// `@here`、`@channel`、`@everyone`
+    Broadcast(RichTextBroadcast),
+    /// 日付
+    Date(RichTextDate),
+    /// 未対応の要素（受信したJSONをそのまま保持）
+    Unknown(Value),
+}
+
+impl_tagged_serde!(RichTextInline {
+    "text" => Text,
+    "link" => Link,
+    "user" => User,
+    "channel" => Channel,
+    "usergroup" => Usergroup,
+    "emoji" => Emoji,
+    "broadcast" => Broadcast,
+    "date" => Date,
+});
+
+/// テキスト
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextText {
+    /// テキスト
+    pub text: String,
+    /// 装飾
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<RichTextStyle>,
+}
+
+/// リンク
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextLink {
+    /// リンク先URL
+    pub url: String,
+    /// 表示テキスト（省略時はURL）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// 装飾
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<RichTextStyle>,
+}
+
+/// ユーザーメンション
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextUser {
+    /// ユーザーID
+    pub user_id: String,
+    /// 装飾
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<RichTextStyle>,
+}
+
+/// チャンネルリンク
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextChannel {
+    /// チャンネルID
+    pub channel_id: String,
+    /// 装飾
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<RichTextStyle>,
+}
+
+/// ユーザーグループメンション
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextUsergroup {
+    /// ユーザーグループID
+    pub usergroup_id: String,
+    /// 装飾
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<RichTextStyle>,
+}
+
+/// 絵文字
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextEmoji {
+    /// 絵文字名（コロンなし）
+    pub name: String,
+    /// Unicodeのコードポイント
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unicode: Option<String>,
+}
+
+/// ブロードキャストメンション
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextBroadcast {
+    /// 範囲（`here`、`channel`、`everyone`）
+    pub range: String,
+}
+
+/// 日付
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichTextDate {
+    /// UNIXタイムスタンプ（秒）
+    pub timestamp: i64,
+    /// 表示形式（例：`{date_short} {time}`）
+    pub format: String,
+    /// クリック時に開くURL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 表示できない場合の代替テキスト
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
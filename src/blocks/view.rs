@@ -0,0 +1,395 @@
+//! モーダル・ホームタブのビューと、送信された入力値
+
+use super::{Block, OptionObject, TextObject};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fmt};
+
+/// モーダルのタイトル・ボタンの最大文字数
+pub const MAX_VIEW_TITLE_LENGTH: usize = 24;
+
+/// ビューに含められるブロックの最大数
+pub const MAX_VIEW_BLOCKS: usize = 100;
+
+/// `private_metadata`の最大文字数
+pub const MAX_PRIVATE_METADATA_LENGTH: usize = 3000;
+
+/// ビューの種類
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewKind {
+    #[default]
+    Modal,
+    Home,
+}
+
+/// モーダルまたはホームタブのビュー
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    /// ビューの種類
+    #[serde(rename = "type")]
+    pub kind: ViewKind,
+    /// タイトル（モーダルでは必須、プレーンテキストのみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<TextObject>,
+    /// 送信ボタンのラベル（入力ブロックを含むモーダルでは必須）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submit: Option<TextObject>,
+    /// 閉じるボタンのラベル
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close: Option<TextObject>,
+    pub blocks: Vec<Block>,
+    /// `view_submission`などで受け取れる任意の文字列
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_metadata: Option<String>,
+    /// `view_submission`でビューを識別するID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_id: Option<String>,
+    /// 閉じたときにすべてのモーダルを閉じるか
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear_on_close: Option<bool>,
+    /// 閉じたときに`view_closed`を送信するか
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_on_close: Option<bool>,
+    /// アプリ側で管理する一意なID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+}
+
+impl View {
+    /// モーダルを作成します
+    pub fn modal(title: impl Into<String>, blocks: Vec<Block>) -> Self {
+        Self {
+            kind: ViewKind::Modal,
+            title: Some(TextObject::plain(title)),
+            blocks,
+            ..Default::default()
+        }
+    }
+
+    /// ホームタブを作成します
+    pub fn home(blocks: Vec<Block>) -> Self {
+        Self {
+            kind: ViewKind::Home,
+            blocks,
+            ..Default::default()
+        }
+    }
+
+    /// 送信ボタンのラベルを設定します
+    pub fn with_submit(mut self, label: impl Into<String>) -> Self {
+        self.submit = Some(TextObject::plain(label));
+        self
+    }
+
+    /// 閉じるボタンのラベルを設定します
+    pub fn with_close(mut self, label: impl Into<String>) -> Self {
+        self.close = Some(TextObject::plain(label));
+        self
+    }
+
+    /// `callback_id`を設定します
+    pub fn with_callback_id(mut self, callback_id: impl Into<String>) -> Self {
+        self.callback_id = Some(callback_id.into());
+        self
+    }
+
+    /// `private_metadata`を設定します
+    pub fn with_private_metadata(mut self, metadata: impl Into<String>) -> Self {
+        self.private_metadata = Some(metadata.into());
+        self
+    }
+
+    /// タイトル・ボタンの文字数、ブロック数、`private_metadata`の文字数がSlackの上限以内か検証します
+    ///
+    /// [`MessageClient`](crate::MessageClient)のビューを表示・更新するメソッドは、送信前にこの検証を行います。
+    pub fn validate(&self) -> Result<(), InvalidView> {
+        let labels = [
+            ("title", &self.title),
+            ("submit", &self.submit),
+            ("close", &self.close),
+        ];
+        for (field, label) in labels {
+            let length = label.as_ref().map_or(0, |l| l.text().chars().count());
+            if length > MAX_VIEW_TITLE_LENGTH {
+                return Err(InvalidView::LabelTooLong { field, length });
+            }
+        }
+        if self.blocks.len() > MAX_VIEW_BLOCKS {
+            return Err(InvalidView::TooManyBlocks(self.blocks.len()));
+        }
+        let length = self
+            .private_metadata
+            .as_ref()
+            .map_or(0, |m| m.chars().count());
+        if length > MAX_PRIVATE_METADATA_LENGTH {
+            return Err(InvalidView::PrivateMetadataTooLong(length));
+        }
+        Ok(())
+    }
+}
+
+/// ビューがSlackの上限を超えている場合のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidView {
+    /// タイトル・ボタンのラベルが[`MAX_VIEW_TITLE_LENGTH`]文字を超えている
+    LabelTooLong {
+        /// フィールド名（`title`、`submit`、`close`）
+        field: &'static str,
+        /// 文字数
+        length: usize,
+    },
+    /// ブロックが[`MAX_VIEW_BLOCKS`]個を超えている
+    TooManyBlocks(usize),
+    /// `private_metadata`が[`MAX_PRIVATE_METADATA_LENGTH`]文字を超えている
+    PrivateMetadataTooLong(usize),
+}
+
+impl fmt::Display for InvalidView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LabelTooLong { field, length } => write!(
+                f,
+                "ビューの{}は{}文字以内にしてください（{}文字）",
+                field, MAX_VIEW_TITLE_LENGTH, length
+            ),
+            Self::TooManyBlocks(count) => write!(
+                f,
+                "ビューのブロックは{}個以内にしてください（{}個）",
+                MAX_VIEW_BLOCKS, count
+            ),
+            Self::PrivateMetadataTooLong(length) => write!(
+                f,
+                "private_metadataは{}文字以内にしてください（{}文字）",
+                MAX_PRIVATE_METADATA_LENGTH, length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidView {}
+
+/// 送信されたビューの入力値（`view.state`）
+///
+/// `values[block_id][action_id]`に各入力要素の値が入ります。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ViewState {
+    #[serde(default)]
+    pub values: HashMap<String, HashMap<String, StateValue>>,
+}
+
+/// 入力要素の値
+///
+/// 要素の種類によって設定されるフィールドが異なります。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateValue {
+    /// 要素の種類（`plain_text_input`、`static_select`など）
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_option: Option<OptionObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_options: Option<Vec<OptionObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_users: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_conversation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_conversations: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_channels: Option<Vec<String>>,
+    /// `YYYY-MM-DD`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_date: Option<String>,
+    /// `HH:mm`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_time: Option<String>,
+    /// UNIX時間
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_date_time: Option<i64>,
+    /// リッチテキスト入力の値
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rich_text_value: Option<Value>,
+}
+
+impl StateValue {
+    /// 単一の値（入力テキスト、選択肢の`value`、ユーザーID、日付など）
+    ///
+    /// 未入力・未選択の場合は`None`を返します。
+    pub fn as_str(&self) -> Option<&str> {
+        self.value
+            .as_deref()
+            .or(self.selected_option.as_ref().map(|o| o.value.as_str()))
+            .or(self.selected_user.as_deref())
+            .or(self.selected_conversation.as_deref())
+            .or(self.selected_channel.as_deref())
+            .or(self.selected_date.as_deref())
+            .or(self.selected_time.as_deref())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 複数選択の値（選択肢の`value`、ユーザーID、チャンネルID）
+    ///
+    /// 単一の値の要素では、値があれば1要素のリストを返します。
+    pub fn as_list(&self) -> Vec<&str> {
+        if let Some(options) = &self.selected_options {
+            return options.iter().map(|o| o.value.as_str()).collect();
+        }
+        let ids = self
+            .selected_users
+            .as_ref()
+            .or(self.selected_conversations.as_ref())
+            .or(self.selected_channels.as_ref());
+        match ids {
+            Some(ids) => ids.iter().map(String::as_str).collect(),
+            None => self.as_str().into_iter().collect(),
+        }
+    }
+
+    fn is_multi(&self) -> bool {
+        self.selected_options.is_some()
+            || self.selected_users.is_some()
+            || self.selected_conversations.is_some()
+            || self.selected_channels.is_some()
+    }
+
+    /// 型に依存しない単純なJSON値（文字列・文字列の配列・数値・`null`）
+    fn to_simple_value(&self) -> Value {
+        if self.is_multi() {
+            Value::from(self.as_list())
+        } else if let Some(value) = self.as_str() {
+            Value::from(value)
+        } else if let Some(timestamp) = self.selected_date_time {
+            Value::from(timestamp)
+        } else if let Some(rich_text) = &self.rich_text_value {
+            rich_text.clone()
+        } else {
+            Value::Null
+        }
+    }
+}
+
+impl ViewState {
+    /// ブロックIDとアクションIDで値を取得します
+    pub fn get(&self, block_id: &str, action_id: &str) -> Option<&StateValue> {
+        self.values.get(block_id)?.get(action_id)
+    }
+
+    /// アクションIDだけで値を検索します（ブロックIDが自動生成される場合など）
+    pub fn find(&self, action_id: &str) -> Option<&StateValue> {
+        self.values
+            .values()
+            .find_map(|actions| actions.get(action_id))
+    }
+
+    /// 入力値をアクションIDをキーとする構造体に変換します
+    ///
+    /// 単一の値は文字列、複数選択は文字列の配列、未入力は`null`として扱うため、
+    /// 各フィールドは`String`・`Option<String>`・`Vec<String>`などで受け取れます。
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use slack_rs::blocks::ViewState;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Incident {
+    ///     title: String,
+    ///     responders: Vec<String>,
+    /// }
+    ///
+    /// let state: ViewState = serde_json::from_value(serde_json::json!({
+    ///     "values": {
+    ///         "b1": {"title": {"type": "plain_text_input", "value": "決済APIの障害"}},
+    ///         "b2": {"responders": {"type": "multi_users_select", "selected_users": ["U1", "U2"]}}
+    ///     }
+    /// })).unwrap();
+    /// let incident: Incident = state.parse().unwrap();
+    /// assert_eq!(incident.responders, vec!["U1", "U2"]);
+    /// ```
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        let fields: Map<String, Value> = self
+            .values
+            .values()
+            .flat_map(|actions| actions.iter())
+            .map(|(action_id, value)| (action_id.clone(), value.to_simple_value()))
+            .collect();
+        serde_json::from_value(Value::Object(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_modal_serialization() {
+        let view = View::modal("障害報告", vec![Block::divider()])
+            .with_submit("送信")
+            .with_callback_id("incident");
+        assert_eq!(
+            serde_json::to_value(&view).unwrap(),
+            json!({
+                "type": "modal",
+                "title": {"type": "plain_text", "text": "障害報告"},
+                "submit": {"type": "plain_text", "text": "送信"},
+                "blocks": [{"type": "divider"}],
+                "callback_id": "incident"
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_limits() {
+        assert!(View::modal("障害報告", vec![])
+            .with_submit("送信")
+            .validate()
+            .is_ok());
+        assert_eq!(
+            View::modal("a".repeat(25), vec![]).validate(),
+            Err(InvalidView::LabelTooLong {
+                field: "title",
+                length: 25
+            })
+        );
+        assert_eq!(
+            View::home(vec![Block::divider(); 101]).validate(),
+            Err(InvalidView::TooManyBlocks(101))
+        );
+        assert_eq!(
+            View::home(vec![])
+                .with_private_metadata("x".repeat(3001))
+                .validate(),
+            Err(InvalidView::PrivateMetadataTooLong(3001))
+        );
+    }
+
+    #[test]
+    fn test_state_values() {
+        let state: ViewState = serde_json::from_value(json!({
+            "values": {
+                "severity": {"severity_select": {
+                    "type": "static_select",
+                    "selected_option": {"text": {"type": "plain_text", "text": "高"}, "value": "high"}
+                }},
+                "due": {"due_date": {"type": "datepicker", "selected_date": "2024-04-01"}},
+                "note": {"note_input": {"type": "plain_text_input", "value": null}}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            state.get("severity", "severity_select").unwrap().as_str(),
+            Some("high")
+        );
+        assert_eq!(state.find("due_date").unwrap().as_str(), Some("2024-04-01"));
+        assert_eq!(state.find("note_input").unwrap().as_str(), None);
+        assert!(state.find("note_input").unwrap().as_list().is_empty());
+    }
+}
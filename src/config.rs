@@ -0,0 +1,487 @@
+//! 環境変数・設定ファイルからのボット設定の読み込み
+//!
+//! 環境変数（`SLACK_BOT_TOKEN`など）と、Docker/Kubernetesのシークレット向けの`_FILE`付きの変数
+//! （`SLACK_BOT_TOKEN_FILE=/run/secrets/bot_token`）、TOMLの設定ファイルから読み込みます。
+//! 同じ項目が両方にある場合は環境変数を優先します。
+
+use crate::types::{ClientSecret, SigningSecret, Token, TokenKind};
+use crate::webhook::DEFAULT_WEBHOOK_PATH;
+pub use crate::webhook::{DEFAULT_COMMANDS_PATH, DEFAULT_INTERACTIONS_PATH};
+use serde::Deserialize;
+use std::{fmt, net::SocketAddr, path::Path};
+
+/// 待ち受けアドレスの既定値
+pub const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:3000";
+
+/// ボットの設定
+#[derive(Debug, Clone)]
+pub struct SlackConfig {
+    /// ボットトークン（`SLACK_BOT_TOKEN`）
+    pub bot_token: Option<Token>,
+    /// アプリレベルトークン（`SLACK_APP_TOKEN`）
+    pub app_token: Option<Token>,
+    /// 署名シークレット（`SLACK_SIGNING_SECRET`）
+    pub signing_secret: Option<SigningSecret>,
+    /// OAuthのクライアントID（`SLACK_CLIENT_ID`）
+    pub client_id: Option<String>,
+    /// OAuthのクライアントシークレット（`SLACK_CLIENT_SECRET`）
+    pub client_secret: Option<ClientSecret>,
+    /// HTTPサーバーの待ち受けアドレス（`SLACK_LISTEN_ADDR`）
+    pub listen_addr: SocketAddr,
+    /// イベントのエンドポイントパス（`SLACK_EVENTS_PATH`）
+    pub events_path: String,
+    /// インタラクションのエンドポイントパス（`SLACK_INTERACTIONS_PATH`）
+    pub interactions_path: String,
+    /// スラッシュコマンドのエンドポイントパス（`SLACK_COMMANDS_PATH`）
+    pub commands_path: String,
+    /// HTTPの代わりにSocket Modeでイベントを受信するか（`SLACK_SOCKET_MODE`）
+    pub socket_mode: bool,
+    /// OAuthで複数のワークスペースにインストールするか（`SLACK_OAUTH`）
+    pub oauth: bool,
+}
+
+/// 設定の問題点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigIssue {
+    /// 必須の項目が設定されていない
+    Missing {
+        /// 環境変数名
+        env: &'static str,
+        /// 設定ファイルのキー
+        key: &'static str,
+    },
+    /// 値の形式が正しくない
+    Invalid {
+        /// 環境変数名・設定ファイルのキー・ファイルパスなど
+        key: String,
+        /// 理由
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { env, key } => write!(
+                f,
+                "{}が設定されていません（設定ファイルでは`{}`）",
+                env, key
+            ),
+            Self::Invalid { key, reason } => write!(f, "{}: {}", key, reason),
+        }
+    }
+}
+
+/// 設定の読み込みエラー
+///
+/// 見つかった問題をまとめて保持します。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "設定に{}件の問題があります", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// 検証前の設定（設定ファイルの形式）
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    bot_token: Option<String>,
+    app_token: Option<String>,
+    signing_secret: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    listen_addr: Option<String>,
+    events_path: Option<String>,
+    interactions_path: Option<String>,
+    commands_path: Option<String>,
+    socket_mode: Option<bool>,
+    oauth: Option<bool>,
+}
+
+impl RawConfig {
+    /// 設定されている項目で上書きします
+    fn merge(self, other: Self) -> Self {
+        Self {
+            bot_token: other.bot_token.or(self.bot_token),
+            app_token: other.app_token.or(self.app_token),
+            signing_secret: other.signing_secret.or(self.signing_secret),
+            client_id: other.client_id.or(self.client_id),
+            client_secret: other.client_secret.or(self.client_secret),
+            listen_addr: other.listen_addr.or(self.listen_addr),
+            events_path: other.events_path.or(self.events_path),
+            interactions_path: other.interactions_path.or(self.interactions_path),
+            commands_path: other.commands_path.or(self.commands_path),
+            socket_mode: other.socket_mode.or(self.socket_mode),
+            oauth: other.oauth.or(self.oauth),
+        }
+    }
+}
+
+/// 環境変数から読み込んだ値
+struct EnvReader<F> {
+    lookup: F,
+    issues: Vec<ConfigIssue>,
+}
+
+impl<F: Fn(&str) -> Option<String>> EnvReader<F> {
+    /// `name`、または`name_FILE`が指すファイルの内容を読み込みます
+    ///
+    /// 空文字列の変数は未設定として扱います。
+    fn string(&mut self, name: &str) -> Option<String> {
+        let file_var = format!("{}_FILE", name);
+        let lookup = |name: &str| (self.lookup)(name).filter(|value| !value.is_empty());
+        match (lookup(name), lookup(&file_var)) {
+            (Some(_), Some(_)) => {
+                self.issues.push(ConfigIssue::Invalid {
+                    key: name.to_string(),
+                    reason: format!("{}と{}の両方が設定されています", name, file_var),
+                });
+                None
+            }
+            (Some(value), None) => Some(value),
+            (None, Some(path)) => match std::fs::read_to_string(&path) {
+                Ok(content) => Some(content.trim_end_matches(['\r', '\n']).to_string()),
+                Err(e) => {
+                    self.issues.push(ConfigIssue::Invalid {
+                        key: file_var,
+                        reason: format!("{}を読み込めません: {}", path, e),
+                    });
+                    None
+                }
+            },
+            (None, None) => None,
+        }
+    }
+
+    fn bool(&mut self, name: &str) -> Option<bool> {
+        let value = self.string(name)?;
+        match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Some(true),
+            "0" | "false" | "no" | "off" => Some(false),
+            _ => {
+                self.issues.push(ConfigIssue::Invalid {
+                    key: name.to_string(),
+                    reason: format!("真偽値として解釈できません: {:?}", value),
+                });
+                None
+            }
+        }
+    }
+
+    fn read(mut self) -> (RawConfig, Vec<ConfigIssue>) {
+        let raw = RawConfig {
+            bot_token: self.string("SLACK_BOT_TOKEN"),
+            app_token: self.string("SLACK_APP_TOKEN"),
+            signing_secret: self.string("SLACK_SIGNING_SECRET"),
+            client_id: self.string("SLACK_CLIENT_ID"),
+            client_secret: self.string("SLACK_CLIENT_SECRET"),
+            listen_addr: self.string("SLACK_LISTEN_ADDR"),
+            events_path: self.string("SLACK_EVENTS_PATH"),
+            interactions_path: self.string("SLACK_INTERACTIONS_PATH"),
+            commands_path: self.string("SLACK_COMMANDS_PATH"),
+            socket_mode: self.bool("SLACK_SOCKET_MODE"),
+            oauth: self.bool("SLACK_OAUTH"),
+        };
+        (raw, self.issues)
+    }
+}
+
+impl SlackConfig {
+    /// 環境変数から読み込みます
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// TOMLの設定ファイルから読み込みます
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let (raw, issues) = read_file(path.as_ref());
+        validate(raw, issues)
+    }
+
+    /// TOMLの文字列から読み込みます
+    pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+        let (raw, issues) = parse_toml(toml, "設定ファイル");
+        validate(raw, issues)
+    }
+
+    /// 設定ファイル（指定された場合）を読み込み、環境変数で上書きします
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let (file, mut issues) = match path {
+            Some(path) => read_file(path),
+            None => Default::default(),
+        };
+        let (env, env_issues) = EnvReader {
+            lookup: |name: &str| std::env::var(name).ok(),
+            issues: Vec::new(),
+        }
+        .read();
+        issues.extend(env_issues);
+        validate(file.merge(env), issues)
+    }
+
+    /// 環境変数の代わりに`lookup`で値を取得して読み込みます
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let (raw, issues) = EnvReader {
+            lookup,
+            issues: Vec::new(),
+        }
+        .read();
+        validate(raw, issues)
+    }
+}
+
+fn read_file(path: &Path) -> (RawConfig, Vec<ConfigIssue>) {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse_toml(&content, &path.display().to_string()),
+        Err(e) => (
+            RawConfig::default(),
+            vec![ConfigIssue::Invalid {
+                key: path.display().to_string(),
+                reason: format!("設定ファイルを読み込めません: {}", e),
+            }],
+        ),
+    }
+}
+
+fn parse_toml(content: &str, name: &str) -> (RawConfig, Vec<ConfigIssue>) {
+    match toml::from_str(content) {
+        Ok(raw) => (raw, Vec::new()),
+        Err(e) => (
+            RawConfig::default(),
+            vec![ConfigIssue::Invalid {
+                key: name.to_string(),
+                reason: e.message().to_string(),
+            }],
+        ),
+    }
+}
+
+/// 必須項目とトークンの種類を検証します
+///
+/// - Socket Modeではアプリレベルトークン、HTTPでは署名シークレットが必要です
+/// - OAuthではクライアントIDとシークレット、それ以外ではボットトークンが必要です
+fn validate(raw: RawConfig, mut issues: Vec<ConfigIssue>) -> Result<SlackConfig, ConfigError> {
+    let socket_mode = raw.socket_mode.unwrap_or(false);
+    let oauth = raw.oauth.unwrap_or(false);
+    // 空のトークンやシークレットは未設定として扱う
+    let non_empty = |value: Option<String>| value.filter(|value| !value.is_empty());
+    let bot_token = non_empty(raw.bot_token).map(Token::new);
+    let app_token = non_empty(raw.app_token).map(Token::new);
+    let signing_secret = non_empty(raw.signing_secret);
+    let client_id = non_empty(raw.client_id);
+    let client_secret = non_empty(raw.client_secret);
+
+    let mut require = |present: bool, env: &'static str, key: &'static str| {
+        if !present {
+            issues.push(ConfigIssue::Missing { env, key });
+        }
+    };
+    if oauth {
+        require(client_id.is_some(), "SLACK_CLIENT_ID", "client_id");
+        require(
+            client_secret.is_some(),
+            "SLACK_CLIENT_SECRET",
+            "client_secret",
+        );
+    } else {
+        require(bot_token.is_some(), "SLACK_BOT_TOKEN", "bot_token");
+    }
+    if socket_mode {
+        require(app_token.is_some(), "SLACK_APP_TOKEN", "app_token");
+    } else {
+        require(
+            signing_secret.is_some(),
+            "SLACK_SIGNING_SECRET",
+            "signing_secret",
+        );
+    }
+
+    if let Some(token) = bot_token.as_ref().filter(|t| t.kind() == TokenKind::App) {
+        issues.push(ConfigIssue::Invalid {
+            key: "SLACK_BOT_TOKEN".to_string(),
+            reason: format!("{}は指定できません", token.kind()),
+        });
+    }
+    if let Some(token) = app_token.as_ref().filter(|t| t.kind() != TokenKind::App) {
+        issues.push(ConfigIssue::Invalid {
+            key: "SLACK_APP_TOKEN".to_string(),
+            reason: format!(
+                "{}が必要ですが、{}が指定されました",
+                TokenKind::App,
+                token.kind()
+            ),
+        });
+    }
+
+    let listen_addr = raw
+        .listen_addr
+        .as_deref()
+        .unwrap_or(DEFAULT_LISTEN_ADDR)
+        .parse::<SocketAddr>();
+    if let Err(e) = &listen_addr {
+        issues.push(ConfigIssue::Invalid {
+            key: "SLACK_LISTEN_ADDR".to_string(),
+            reason: e.to_string(),
+        });
+    }
+
+    let mut path = |value: Option<String>, env: &str, default: &str| {
+        let value = value.unwrap_or_else(|| default.to_string());
+        if !value.starts_with('/') {
+            issues.push(ConfigIssue::Invalid {
+                key: env.to_string(),
+                reason: format!("パスは`/`で始まる必要があります: {:?}", value),
+            });
+        }
+        value
+    };
+    let events_path = path(raw.events_path, "SLACK_EVENTS_PATH", DEFAULT_WEBHOOK_PATH);
+    let interactions_path = path(
+        raw.interactions_path,
+        "SLACK_INTERACTIONS_PATH",
+        DEFAULT_INTERACTIONS_PATH,
+    );
+    let commands_path = path(
+        raw.commands_path,
+        "SLACK_COMMANDS_PATH",
+        DEFAULT_COMMANDS_PATH,
+    );
+
+    match listen_addr {
+        Ok(listen_addr) if issues.is_empty() => Ok(SlackConfig {
+            bot_token,
+            app_token,
+            signing_secret: signing_secret.map(SigningSecret::new),
+            client_id,
+            client_secret: client_secret.map(ClientSecret::new),
+            listen_addr,
+            events_path,
+            interactions_path,
+            commands_path,
+            socket_mode,
+            oauth,
+        }),
+        _ => Err(ConfigError { issues }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_from_env_with_defaults() {
+        let config = SlackConfig::from_lookup(lookup(&[
+            ("SLACK_BOT_TOKEN", "xoxb-1"),
+            ("SLACK_SIGNING_SECRET", "secret"),
+        ]))
+        .unwrap();
+        assert_eq!(config.bot_token.unwrap().kind(), TokenKind::Bot);
+        assert_eq!(config.listen_addr.to_string(), DEFAULT_LISTEN_ADDR);
+        assert_eq!(config.events_path, DEFAULT_WEBHOOK_PATH);
+        assert!(!config.socket_mode);
+    }
+
+    #[test]
+    fn test_reports_all_issues_at_once() {
+        let error = SlackConfig::from_lookup(lookup(&[
+            ("SLACK_SOCKET_MODE", "true"),
+            ("SLACK_APP_TOKEN", "xoxb-1"),
+            ("SLACK_LISTEN_ADDR", "localhost"),
+        ]))
+        .unwrap_err();
+        assert_eq!(error.issues.len(), 3);
+        assert_eq!(
+            error.issues[0],
+            ConfigIssue::Missing {
+                env: "SLACK_BOT_TOKEN",
+                key: "bot_token"
+            }
+        );
+        assert!(error.to_string().starts_with("設定に3件の問題があります"));
+    }
+
+    #[test]
+    fn test_empty_values_are_missing() {
+        let error = SlackConfig::from_lookup(lookup(&[
+            ("SLACK_BOT_TOKEN", ""),
+            ("SLACK_SIGNING_SECRET", ""),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            error.issues,
+            vec![
+                ConfigIssue::Missing {
+                    env: "SLACK_BOT_TOKEN",
+                    key: "bot_token"
+                },
+                ConfigIssue::Missing {
+                    env: "SLACK_SIGNING_SECRET",
+                    key: "signing_secret"
+                },
+            ]
+        );
+
+        let error = SlackConfig::from_toml_str("bot_token = \"\"\nsigning_secret = \"secret\"")
+            .unwrap_err();
+        assert_eq!(error.issues.len(), 1);
+    }
+
+    #[test]
+    fn test_file_variant() {
+        let path = std::env::temp_dir().join(format!("slack_rs_secret_{}", std::process::id()));
+        std::fs::write(&path, "xapp-1\n").unwrap();
+        let config = SlackConfig::from_lookup(lookup(&[
+            ("SLACK_BOT_TOKEN", "xoxb-1"),
+            ("SLACK_APP_TOKEN_FILE", path.to_str().unwrap()),
+            ("SLACK_SOCKET_MODE", "1"),
+        ]))
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.app_token.unwrap().expose_secret(), "xapp-1");
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config = SlackConfig::from_toml_str(
+            r#"
+            signing_secret = "secret"
+            client_id = "123.456"
+            client_secret = "client-secret"
+            oauth = true
+            listen_addr = "0.0.0.0:8080"
+            events_path = "/slack/events"
+            "#,
+        )
+        .unwrap();
+        assert!(config.oauth);
+        assert!(config.bot_token.is_none());
+        assert_eq!(config.listen_addr.port(), 8080);
+        assert_eq!(config.events_path, "/slack/events");
+        let debug = format!("{:?}", config);
+        assert!(debug.contains("ClientSecret(***)") && !debug.contains("client-secret"));
+
+        let error = SlackConfig::from_toml_str("bot_tokne = \"xoxb-1\"").unwrap_err();
+        assert!(matches!(error.issues[0], ConfigIssue::Invalid { .. }));
+    }
+}
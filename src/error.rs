@@ -0,0 +1,83 @@
+//! クレート共通のエラー型
+
+use std::{fmt, time::Duration};
+
+/// Slackとの通信で発生するエラー
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SlackError {
+    /// Slackがエラーコードを返した（`invalid_payload`、`channel_is_archived`など）
+    Api {
+        /// エラーコード
+        code: String,
+    },
+    /// レート制限により、再試行しても送信できなかった
+    RateLimited {
+        /// Slackが指定した再試行までの待ち時間
+        retry_after: Option<Duration>,
+    },
+    /// 予期しないHTTPステータス
+    Http { status: u16, body: String },
+    /// 接続やリクエストの送信に失敗した
+    Transport(String),
+    /// リクエスト・レスポンスのJSON変換に失敗した
+    Serialization(serde_json::Error),
+}
+
+impl SlackError {
+    /// Slackのエラーコード（`Api`の場合のみ）
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Api { code } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// 時間をおいて再試行すると成功する可能性があるか
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Transport(_) => true,
+            Self::Http { status, .. } => *status >= 500,
+            Self::Api { code } => code == "rollup_error",
+            Self::Serialization(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for SlackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Api { code } => write!(f, "Slack APIエラー: {}", code),
+            Self::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "レート制限により送信できませんでした（{}秒後に再試行可能）",
+                retry_after.as_secs()
+            ),
+            Self::RateLimited { retry_after: None } => {
+                write!(f, "レート制限により送信できませんでした")
+            }
+            Self::Http { status, body } => {
+                write!(f, "予期しないHTTPステータス {}: {}", status, body)
+            }
+            Self::Transport(message) => write!(f, "通信エラー: {}", message),
+            Self::Serialization(e) => write!(f, "JSONの変換に失敗しました: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SlackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Serialization(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SlackError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e)
+    }
+}
@@ -6,7 +6,9 @@ use axum::{
     Json, Router,
 };
 #[cfg(feature = "events")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "events")]
+use serde_json::Value;
 #[cfg(feature = "events")]
 use slack_morphism::{
     api::SlackApiChatPostMessageRequest,
@@ -17,8 +19,14 @@ use slack_morphism::{
 #[cfg(feature = "events")]
 use tracing::{error, info};
 
+#[cfg(feature = "events")]
+use crate::message::MessageMetadata;
+#[cfg(feature = "events")]
+use crate::mrkdwn::{self, Entity};
 #[cfg(feature = "events")]
 use crate::oauth::OAuthConfig;
+#[cfg(feature = "events")]
+use crate::types::{AppId, ChannelId, InvalidId, TeamId, Ts, UserId};
 
 /// Slackから受信するイベントの種類を表す列挙型
 #[derive(Debug, Clone)]
@@ -32,54 +40,294 @@ pub enum Event {
     /// メンションイベント
     AppMention {
         /// チャンネルID
-        channel: String,
+        channel: ChannelId,
         /// メッセージのタイムスタンプ
-        ts: String,
+        ts: Ts,
+        /// スレッド内でメンションされた場合は親メッセージのタイムスタンプ
+        thread_ts: Option<Ts>,
         /// メッセージのテキスト
         text: String,
         /// チームID
-        team_id: Option<String>,
+        team_id: Option<TeamId>,
     },
     /// メッセージイベント
     Message {
         /// チャンネルID
-        channel: String,
+        channel: ChannelId,
         /// メッセージのテキスト
         text: String,
         /// チームID
-        team_id: Option<String>,
+        team_id: Option<TeamId>,
+        /// メッセージのメタデータ
+        metadata: Option<MessageMetadata>,
+    },
+    /// メタデータ付きのメッセージが投稿された
+    MessageMetadataPosted {
+        /// チャンネルID
+        channel: ChannelId,
+        /// メッセージのタイムスタンプ
+        message_ts: Ts,
+        /// メッセージを投稿したアプリのID
+        app_id: Option<AppId>,
+        /// メッセージを投稿したユーザーのID
+        user: Option<UserId>,
+        metadata: MessageMetadata,
+        /// チームID
+        team_id: Option<TeamId>,
+    },
+    /// メッセージのメタデータが更新された
+    MessageMetadataUpdated {
+        /// チャンネルID
+        channel: ChannelId,
+        /// メッセージのタイムスタンプ
+        message_ts: Ts,
+        previous_metadata: Option<MessageMetadata>,
+        metadata: MessageMetadata,
+        /// チームID
+        team_id: Option<TeamId>,
+    },
+    /// メタデータ付きのメッセージが削除された
+    MessageMetadataDeleted {
+        /// チャンネルID
+        channel: ChannelId,
+        /// メッセージのタイムスタンプ
+        message_ts: Ts,
+        previous_metadata: Option<MessageMetadata>,
+        /// チームID
+        team_id: Option<TeamId>,
+    },
+    /// リンク共有イベント（アプリに登録したドメインのURLが投稿された）
+    LinkShared {
+        /// チャンネルID（メッセージ作成中のプレビューでは`COMPOSER`）
+        channel: ChannelId,
+        /// リンクを投稿したユーザーのID
+        user: UserId,
+        /// リンクを含むメッセージのタイムスタンプ（メッセージ作成中のプレビューでは`None`）
+        message_ts: Option<Ts>,
+        /// 共有されたリンク
+        links: Vec<SharedLink>,
+        /// `chat.unfurl`で`source`と組み合わせて指定するID
+        unfurl_id: Option<String>,
+        /// リンクが共有された場所（`conversations_history`または`composer`）
+        source: Option<String>,
+        /// チームID
+        team_id: Option<TeamId>,
     },
     /// その他のイベント
     Other,
 }
 
+/// [`Event::LinkShared`]で共有されたリンク
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "events")]
+pub struct SharedLink {
+    /// ドメイン（`example.com`など）
+    pub domain: String,
+    /// URL
+    pub url: String,
+}
+
+#[cfg(feature = "events")]
+impl Event {
+    /// メッセージまたはメンションのテキスト
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::AppMention { text, .. } | Self::Message { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// イベントが発生したワークスペースのチームID
+    pub fn team_id(&self) -> Option<&TeamId> {
+        match self {
+            Self::AppMention { team_id, .. }
+            | Self::Message { team_id, .. }
+            | Self::MessageMetadataPosted { team_id, .. }
+            | Self::MessageMetadataUpdated { team_id, .. }
+            | Self::MessageMetadataDeleted { team_id, .. }
+            | Self::LinkShared { team_id, .. } => team_id.as_ref(),
+            Self::UrlVerification { .. } | Self::Other => None,
+        }
+    }
+
+    /// メンションが属するスレッドの親メッセージのタイムスタンプ
+    ///
+    /// スレッド外でメンションされた場合は、メンション自体のタイムスタンプを返します。
+    /// [`MessageClient::load_thread`](crate::MessageClient::load_thread)に渡すとスレッド全体を取得できます。
+    pub fn thread_root(&self) -> Option<&Ts> {
+        match self {
+            Self::AppMention { ts, thread_ts, .. } => Some(thread_ts.as_ref().unwrap_or(ts)),
+            _ => None,
+        }
+    }
+
+    /// テキストに含まれるメンションやリンク
+    pub fn entities(&self) -> Vec<Entity> {
+        self.text().map(mrkdwn::parse).unwrap_or_default()
+    }
+
+    /// テキストでメンションされたユーザー（出現順、重複なし）
+    ///
+    /// [`AppMention`](Self::AppMention)ではボット自身も含まれます。
+    pub fn mentioned_users(&self) -> Vec<UserId> {
+        let mut users: Vec<UserId> = Vec::new();
+        for entity in self.entities() {
+            if let Entity::User { id, .. } = entity {
+                if !users.contains(&id) {
+                    users.push(id);
+                }
+            }
+        }
+        users
+    }
+
+    /// リクエストボディのJSONからイベントを作成します
+    ///
+    /// slack-morphismが扱えないメタデータのイベントと、メッセージのメタデータもここで読み取ります。
+    pub fn from_json(body: &str) -> Result<Self, serde_json::Error> {
+        let mut value: Value = serde_json::from_str(body)?;
+        let team_id = value
+            .get("team_id")
+            .and_then(Value::as_str)
+            .and_then(|id| id.parse().ok());
+        if let Some(Ok(event)) = value.get("event").map(MetadataEvent::deserialize) {
+            return Ok(event.into_event(team_id));
+        }
+
+        // slack-morphismはメタデータの値を文字列としてしか扱えないため、取り除いてから変換する
+        let metadata = value
+            .get_mut("event")
+            .and_then(Value::as_object_mut)
+            .and_then(|event| event.remove("metadata"));
+        let mut event = Self::try_from(serde_json::from_value::<SlackPushEvent>(value)?)
+            .map_err(serde::de::Error::custom)?;
+        if let Self::Message { metadata: slot, .. } = &mut event {
+            *slot = metadata.and_then(|m| serde_json::from_value(m).ok());
+        }
+        Ok(event)
+    }
+}
+
+/// メタデータのイベント（slack-morphism未対応）
 #[cfg(feature = "events")]
-impl From<SlackPushEvent> for Event {
-    fn from(event: SlackPushEvent) -> Self {
-        match event {
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum MetadataEvent {
+    #[serde(rename = "message_metadata_posted")]
+    Posted {
+        channel_id: ChannelId,
+        message_ts: Ts,
+        app_id: Option<AppId>,
+        user_id: Option<UserId>,
+        metadata: MessageMetadata,
+    },
+    #[serde(rename = "message_metadata_updated")]
+    Updated {
+        channel_id: ChannelId,
+        message_ts: Ts,
+        previous_metadata: Option<MessageMetadata>,
+        metadata: MessageMetadata,
+    },
+    #[serde(rename = "message_metadata_deleted")]
+    Deleted {
+        channel_id: ChannelId,
+        message_ts: Ts,
+        previous_metadata: Option<MessageMetadata>,
+    },
+}
+
+#[cfg(feature = "events")]
+impl MetadataEvent {
+    fn into_event(self, team_id: Option<TeamId>) -> Event {
+        match self {
+            Self::Posted {
+                channel_id,
+                message_ts,
+                app_id,
+                user_id,
+                metadata,
+            } => Event::MessageMetadataPosted {
+                channel: channel_id,
+                message_ts,
+                app_id,
+                user: user_id,
+                metadata,
+                team_id,
+            },
+            Self::Updated {
+                channel_id,
+                message_ts,
+                previous_metadata,
+                metadata,
+            } => Event::MessageMetadataUpdated {
+                channel: channel_id,
+                message_ts,
+                previous_metadata,
+                metadata,
+                team_id,
+            },
+            Self::Deleted {
+                channel_id,
+                message_ts,
+                previous_metadata,
+            } => Event::MessageMetadataDeleted {
+                channel: channel_id,
+                message_ts,
+                previous_metadata,
+                team_id,
+            },
+        }
+    }
+}
+
+/// タイムスタンプの形式が正しくない場合はエラーになります
+#[cfg(feature = "events")]
+impl TryFrom<SlackPushEvent> for Event {
+    type Error = InvalidId;
+
+    fn try_from(event: SlackPushEvent) -> Result<Self, Self::Error> {
+        Ok(match event {
             SlackPushEvent::UrlVerification(ver) => Self::UrlVerification {
                 challenge: ver.challenge,
             },
             SlackPushEvent::EventCallback(callback) => match callback.event {
                 SlackEventCallbackBody::AppMention(mention) => Self::AppMention {
-                    channel: mention.channel.to_string(),
-                    ts: mention.origin.ts.to_string(),
+                    channel: mention.channel.into(),
+                    ts: mention.origin.ts.try_into()?,
+                    thread_ts: mention.origin.thread_ts.map(Ts::try_from).transpose()?,
                     text: mention.content.text.expect("メンションテキストが空です"),
-                    team_id: Some(callback.team_id.to_string()),
+                    team_id: Some(callback.team_id.clone().into()),
                 },
                 SlackEventCallbackBody::Message(message) => Self::Message {
-                    channel: message
-                        .origin
-                        .channel
-                        .expect("チャンネルIDが空です")
-                        .to_string(),
+                    channel: message.origin.channel.expect("チャンネルIDが空です").into(),
                     text: message.content.unwrap().text.unwrap_or_default(),
-                    team_id: Some(callback.team_id.to_string()),
+                    team_id: Some(callback.team_id.clone().into()),
+                    metadata: None,
+                },
+                SlackEventCallbackBody::LinkShared(shared) => Self::LinkShared {
+                    channel: shared.channel.into(),
+                    user: shared.user.into(),
+                    // メッセージ作成中のプレビューでは`message_ts`がタイムスタンプの形式ではない
+                    message_ts: match shared.source.as_deref() {
+                        Some("composer") => None,
+                        _ => Some(shared.message_ts.try_into()?),
+                    },
+                    links: shared
+                        .links
+                        .into_iter()
+                        .map(|link| SharedLink {
+                            domain: link.domain,
+                            url: link.url.to_string(),
+                        })
+                        .collect(),
+                    unfurl_id: shared.unfurl_id.map(|id| id.to_string()),
+                    source: shared.source,
+                    team_id: Some(callback.team_id.clone().into()),
                 },
                 _ => Self::Other,
             },
             _ => Self::Other,
-        }
+        })
     }
 }
 
@@ -103,7 +351,7 @@ async fn handle_slack_event(
 ) -> Result<Response, String> {
     info!("Slackイベントを受信: {:?}", event);
 
-    let event: Event = event.into();
+    let event = Event::try_from(event).map_err(|e| e.to_string())?;
     match event {
         Event::UrlVerification { challenge } => {
             Ok(Json(ChallengeResponse { challenge }).into_response())
@@ -112,6 +360,7 @@ async fn handle_slack_event(
             channel,
             text,
             team_id,
+            ..
         } => {
             if let Err(e) = handle_message_event(channel, text, team_id, config).await {
                 error!("メッセージイベントの処理に失敗: {}", e);
@@ -129,6 +378,16 @@ async fn handle_slack_event(
             }
             Ok("ok".into_response())
         }
+        Event::LinkShared { links, .. } => {
+            info!("リンク共有イベントを受信: {}件", links.len());
+            Ok("ok".into_response())
+        }
+        Event::MessageMetadataPosted { .. }
+        | Event::MessageMetadataUpdated { .. }
+        | Event::MessageMetadataDeleted { .. } => {
+            info!("メタデータイベントを受信");
+            Ok("ok".into_response())
+        }
         Event::Other => {
             info!("未対応のイベントタイプ");
             Ok("ok".into_response())
@@ -138,9 +397,9 @@ async fn handle_slack_event(
 
 #[cfg(feature = "events")]
 async fn handle_message_event(
-    channel: String,
+    channel: ChannelId,
     _text: String,
-    team_id: Option<String>,
+    team_id: Option<TeamId>,
     config: OAuthConfig,
 ) -> Result<(), String> {
     if let Some(team_id) = team_id {
@@ -150,7 +409,7 @@ async fn handle_message_event(
 
         // トークンの取得
         let storage = config.token_storage.read().await;
-        if let Some(token_response) = storage.get_token(&team_id) {
+        if let Some(token_response) = storage.get_token(team_id.as_str()) {
             let token = SlackApiToken::new(SlackApiTokenValue(token_response.access_token.clone()));
             let session = client.open_session(&token);
 
@@ -174,9 +433,9 @@ async fn handle_message_event(
 
 #[cfg(feature = "events")]
 async fn handle_app_mention_event(
-    channel: String,
+    channel: ChannelId,
     _text: String,
-    team_id: Option<String>,
+    team_id: Option<TeamId>,
     config: OAuthConfig,
 ) -> Result<(), String> {
     if let Some(team_id) = team_id {
@@ -186,7 +445,7 @@ async fn handle_app_mention_event(
 
         // トークンの取得
         let storage = config.token_storage.read().await;
-        if let Some(token_response) = storage.get_token(&team_id) {
+        if let Some(token_response) = storage.get_token(team_id.as_str()) {
             let token = SlackApiToken::new(SlackApiTokenValue(token_response.access_token.clone()));
             let session = client.open_session(&token);
 
@@ -207,3 +466,159 @@ async fn handle_app_mention_event(
     }
     Ok(())
 }
+
+#[cfg(all(test, feature = "events"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_mention_entities() {
+        let event = Event::AppMention {
+            channel: ChannelId::new("C1").unwrap(),
+            ts: "1700000000.000100".parse().unwrap(),
+            thread_ts: None,
+            text: "<@U0BOT> <https://example.com|見て> <#C2|random> <@U1> <@U0BOT>".to_string(),
+            team_id: None,
+        };
+        let bot = UserId::new("U0BOT").unwrap();
+        assert_eq!(
+            &event.entities()[..3],
+            &[
+                Entity::user(&bot),
+                Entity::link("https://example.com", "見て"),
+                Entity::Channel {
+                    id: ChannelId::new("C2").unwrap(),
+                    label: Some("random".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            event.mentioned_users(),
+            vec![bot, UserId::new("U1").unwrap()]
+        );
+        assert!(Event::Other.entities().is_empty());
+    }
+
+    #[test]
+    fn test_thread_root() {
+        let mention = |thread_ts: Option<&str>| Event::AppMention {
+            channel: ChannelId::new("C1").unwrap(),
+            ts: "1700000000.000200".parse().unwrap(),
+            thread_ts: thread_ts.map(|ts| ts.parse().unwrap()),
+            text: "<@U0BOT>".to_string(),
+            team_id: None,
+        };
+        let root = |event: Event| event.thread_root().map(Ts::to_string);
+        assert_eq!(root(mention(None)).as_deref(), Some("1700000000.000200"));
+        assert_eq!(
+            root(mention(Some("1700000000.000100"))).as_deref(),
+            Some("1700000000.000100")
+        );
+        assert_eq!(Event::Other.thread_root(), None);
+    }
+
+    fn callback(event: serde_json::Value) -> String {
+        serde_json::json!({
+            "token": "t",
+            "team_id": "T1",
+            "api_app_id": "A1",
+            "event": event,
+            "type": "event_callback",
+            "event_id": "Ev1",
+            "event_time": 1700000000
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_message_with_metadata_from_json() {
+        let body = callback(serde_json::json!({
+            "type": "message",
+            "channel": "C1",
+            "user": "U1",
+            "text": "デプロイを開始しました",
+            "ts": "1700000000.000100",
+            "metadata": {
+                "event_type": "deployment_started",
+                "event_payload": {"id": "d-123", "replicas": 3}
+            }
+        }));
+        let Event::Message { metadata, .. } = Event::from_json(&body).unwrap() else {
+            panic!("メッセージイベントではありません");
+        };
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.event_type, "deployment_started");
+        assert_eq!(metadata.event_payload["replicas"], 3);
+    }
+
+    #[test]
+    fn test_link_shared_in_composer_from_json() {
+        let body = callback(serde_json::json!({
+            "type": "link_shared",
+            "channel": "COMPOSER",
+            "is_bot_user_member": true,
+            "user": "U1",
+            "message_ts": "U1-909b5454-75f8-4ac4-b325-1b40e230bbd8-gryl3kb80b3wm49ihzoo35fyqoq08n2y",
+            "unfurl_id": "C1.1700000000.000100.7e0c5b3c",
+            "source": "composer",
+            "links": [{"domain": "example.com", "url": "https://example.com/issues/1"}],
+            "event_ts": "1700000000.000200"
+        }));
+        match Event::from_json(&body).unwrap() {
+            Event::LinkShared {
+                message_ts,
+                unfurl_id,
+                source,
+                links,
+                ..
+            } => {
+                assert_eq!(message_ts, None);
+                assert_eq!(unfurl_id.as_deref(), Some("C1.1700000000.000100.7e0c5b3c"));
+                assert_eq!(source.as_deref(), Some("composer"));
+                assert_eq!(links[0].url, "https://example.com/issues/1");
+            }
+            event => panic!("想定外のイベント: {:?}", event),
+        }
+
+        // 投稿済みのメッセージでタイムスタンプの形式が正しくない場合はエラーになる
+        let body = callback(serde_json::json!({
+            "type": "app_mention",
+            "channel": "C1",
+            "user": "U1",
+            "text": "<@U0BOT>",
+            "ts": "not-a-ts",
+            "event_ts": "1700000000.000200"
+        }));
+        assert!(Event::from_json(&body).is_err());
+    }
+
+    #[test]
+    fn test_metadata_deleted_from_json() {
+        let body = callback(serde_json::json!({
+            "type": "message_metadata_deleted",
+            "app_id": "A1",
+            "bot_id": "B1",
+            "user_id": "U1",
+            "team_id": "T1",
+            "channel_id": "C1",
+            "message_ts": "1700000000.000100",
+            "previous_metadata": {"event_type": "deployment_started", "event_payload": {"id": "d-123"}},
+            "deleted_ts": "1700000100.000000",
+            "event_ts": "1700000100.000000"
+        }));
+        match Event::from_json(&body).unwrap() {
+            Event::MessageMetadataDeleted {
+                channel,
+                message_ts,
+                previous_metadata,
+                team_id,
+            } => {
+                assert_eq!(channel, "C1");
+                assert_eq!(message_ts, Ts::from_parts(1700000000, 100));
+                assert_eq!(previous_metadata.unwrap().event_payload["id"], "d-123");
+                assert_eq!(team_id.unwrap(), "T1");
+            }
+            event => panic!("想定外のイベント: {:?}", event),
+        }
+    }
+}
@@ -1,20 +1,50 @@
+#[cfg(feature = "events")]
+pub mod app;
 pub mod blocks;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod error;
 pub mod events;
+pub mod manifest;
+#[cfg(feature = "markdown")]
+pub mod markdown;
 pub mod message;
+pub mod mrkdwn;
 pub mod oauth;
+pub mod retry;
+#[cfg(feature = "socket_mode")]
 pub mod socket_mode;
 pub mod types;
+pub mod unfurl;
 pub mod webhook;
 
 // 公開APIのエクスポート
-pub use blocks::Block;
 #[cfg(feature = "events")]
-pub use events::Event;
-pub use message::MessageClient;
-pub use types::{SigningSecret, Token};
+pub use app::{InvalidApp, Middleware, SlackApp};
+pub use blocks::{Block, TextObject, View, ViewState};
+#[cfg(feature = "config")]
+pub use config::{ConfigError, ConfigIssue, SlackConfig};
+pub use error::SlackError;
+#[cfg(feature = "events")]
+pub use events::{Event, SharedLink};
+pub use manifest::{AppManifest, SlashCommand};
+pub use message::{
+    Attachment, AuthInfo, Bookmark, BookmarkUpdate, Channel, ChannelText, ConversationType,
+    HistoryMessage, HistoryOptions, IncomingWebhookClient, ListChannelsOptions, LongMessageOptions,
+    MessageClient, MessageMetadata, MessageStream, MessageStreamOptions, PinnedItem, PostedMessage,
+    Presence, ReactedItem, Reaction, StatusReactions, User, UserCache, UserProfile, ViewInfo,
+    WebhookMessage,
+};
+pub use retry::RetryConfig;
+pub use types::{
+    AppId, BotId, ChannelId, ClientSecret, EnterpriseId, FileId, InvalidId, InvalidTokenKind,
+    SigningSecret, TeamId, Token, TokenKind, Ts, UserId, UsergroupId,
+};
+#[cfg(feature = "events")]
+pub use unfurl::{UnfurlProvider, UnfurlRegistry};
 pub use webhook::{
     create_app, create_app_with_path, handle_push_event, AppState, NoopHandler, SlackEventHandler,
-    DEFAULT_WEBHOOK_PATH,
+    DEFAULT_COMMANDS_PATH, DEFAULT_INTERACTIONS_PATH, DEFAULT_WEBHOOK_PATH,
 };
 
 // 一時的なslack-morphism型のre-export
@@ -22,7 +52,7 @@ pub use webhook::{
 pub use slack_morphism::{
     api::SlackApiChatPostMessageRequest,
     blocks::{SlackBlock, SlackBlockText, SlackSectionBlock},
-    events::{SlackEventCallbackBody, SlackPushEvent},
+    events::{SlackCommandEvent, SlackEventCallbackBody, SlackInteractionEvent, SlackPushEvent},
     prelude::{
         SlackApiToken, SlackApiTokenValue, SlackChannelId, SlackClient, SlackMessageContent,
         SlackTs,
@@ -34,12 +64,20 @@ mod tests {
     #[test]
     fn test_socket_mode_module_exists() {
         // モジュールが正しくエクスポートされていることを確認
-        assert!(true);
+        let _client = crate::socket_mode::SocketModeClient::new("xapp-test").unwrap();
+    }
+
+    #[test]
+    fn test_socket_mode_requires_app_token() {
+        let error = crate::socket_mode::SocketModeClient::new("xoxb-test")
+            .err()
+            .unwrap();
+        assert_eq!(error.expected, Some(crate::TokenKind::App));
     }
 
     #[test]
     fn test_webhook_module_exists() {
         // webhookモジュールが正しくエクスポートされていることを確認
-        assert!(true);
+        assert_eq!(crate::DEFAULT_WEBHOOK_PATH, "/push");
     }
 }
@@ -1,38 +1,321 @@
+//! slack_rsのコマンドラインツール
+//!
+//! メッセージの投稿やファイルのアップロード、イベントの受信と署名の検証を行います。
+//! より詳細な使用例については examples/ ディレクトリを参照してください。
+
+use async_trait::async_trait;
 use axum::{routing::get, Router};
-use slack_rs::{create_app, SigningSecret};
-use std::net::SocketAddr;
+use clap::{Args, Parser, Subcommand};
+use slack_morphism::signature_verifier::SlackEventSignatureVerifier;
+use slack_rs::{
+    socket_mode::SocketModeClient, Block, ChannelId, Event, MessageClient, SigningSecret, SlackApp,
+    SlackConfig, SlackEventHandler, Token, Ts,
+};
+use std::{
+    error::Error,
+    io::Read,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::mpsc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
+#[derive(Parser)]
+#[command(
+    name = "slack_rs",
+    version,
+    about = "Slackへの投稿とイベント受信のためのツール"
+)]
+struct Cli {
+    /// 詳細なログを出力します
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// メッセージを投稿します（本文は--text、--file、標準入力の順に読み込みます）
+    Post {
+        /// 投稿先のチャンネルID
+        channel: ChannelId,
+        /// 本文
+        #[arg(long, conflicts_with = "file")]
+        text: Option<String>,
+        /// 本文を読み込むファイル（`-`で標準入力）
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// 本文をBlock KitのJSON（ブロックの配列または`{"blocks": [...]}`）として扱います
+        #[arg(long)]
+        blocks: bool,
+        /// スレッドに返信する場合は親メッセージのタイムスタンプ
+        #[arg(long)]
+        thread_ts: Option<Ts>,
+        #[command(flatten)]
+        bot: BotArgs,
+    },
+    /// ファイルをアップロードします
+    Upload {
+        /// アップロード先のチャンネルID
+        channel: ChannelId,
+        /// アップロードするファイル
+        path: PathBuf,
+        /// Slack上のファイル名（省略時は元のファイル名）
+        #[arg(long)]
+        filename: Option<String>,
+        #[command(flatten)]
+        bot: BotArgs,
+    },
+    /// webhookサーバー（設定でSocket Modeが有効な場合はSocket Mode）で受信したイベントをログに出力します
+    Serve {
+        /// TOMLの設定ファイル（環境変数で上書きされます）
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    /// Socket Modeで受信したイベントをJSON Linesで標準出力に書き出します
+    Socket {
+        /// アプリレベルトークン（`xapp-`）
+        #[arg(long, env = "SLACK_APP_TOKEN", hide_env_values = true)]
+        app_token: String,
+    },
+    /// 保存したリクエストの署名を検証します
+    VerifySignature {
+        /// `X-Slack-Request-Timestamp`ヘッダーの値
+        #[arg(long)]
+        timestamp: String,
+        /// `X-Slack-Signature`ヘッダーの値
+        #[arg(long)]
+        signature: String,
+        /// リクエストボディのファイル（省略時または`-`で標準入力）
+        #[arg(long)]
+        body: Option<PathBuf>,
+        /// 署名シークレット
+        #[arg(long, env = "SLACK_SIGNING_SECRET", hide_env_values = true)]
+        signing_secret: String,
+        /// タイムスタンプの許容秒数（指定した場合のみ確認します）
+        #[arg(long)]
+        max_age: Option<u64>,
+    },
+    /// トークンが有効かを確認します
+    AuthTest {
+        #[command(flatten)]
+        bot: BotArgs,
+    },
+}
+
+#[derive(Args)]
+struct BotArgs {
+    /// ボットトークン（`xoxb-`）またはユーザートークン（`xoxp-`）
+    #[arg(long, env = "SLACK_BOT_TOKEN", hide_env_values = true)]
+    token: String,
+}
+
+impl BotArgs {
+    fn client(&self) -> Result<MessageClient, Box<dyn Error>> {
+        Ok(MessageClient::new(Token::new(self.token.clone()))?)
+    }
+}
+
+/// 受信したイベントをログに出力するハンドラ
+#[derive(Clone)]
+struct LoggingHandler;
+
+#[async_trait]
+impl SlackEventHandler for LoggingHandler {
+    async fn handle_event(
+        &self,
+        event: Event,
+        _client: &MessageClient,
+    ) -> Result<(), Box<dyn Error>> {
+        info!("イベントを受信: {:?}", event);
+        Ok(())
+    }
+}
+
 #[tokio::main]
-async fn main() {
-    // このファイルは基本的な使用例を示すためのものです。
-    // より詳細な使用例については examples/ ディレクトリを参照してください。
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    // ロギングの初期化
+    // 標準出力はコマンドの出力に使うため、ログは標準エラー出力に書き出す
     FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
+        .with_max_level(if cli.verbose {
+            Level::DEBUG
+        } else {
+            Level::INFO
+        })
+        .with_writer(std::io::stderr)
         .compact()
         .init();
 
-    info!("基本的なWebhookサーバーを起動します");
+    match run(cli.command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Post {
+            channel,
+            text,
+            file,
+            blocks,
+            thread_ts,
+            bot,
+        } => {
+            let client = bot.client()?;
+            let body = match text {
+                Some(text) => text,
+                None => read_input(file.as_deref())?,
+            };
+            let posted = match (blocks, thread_ts) {
+                (true, Some(thread_ts)) => {
+                    client
+                        .reply_blocks_to_thread(&channel, &thread_ts, parse_blocks(&body)?)
+                        .await?
+                }
+                (true, None) => client.send_blocks(&channel, parse_blocks(&body)?).await?,
+                (false, Some(thread_ts)) => {
+                    client.reply_to_thread(&channel, &thread_ts, &body).await?
+                }
+                (false, None) => client.send_text(&channel, &body).await?,
+            };
+            println!(
+                "{}",
+                serde_json::json!({"channel": posted.channel, "ts": posted.ts})
+            );
+        }
+        Command::Upload {
+            channel,
+            path,
+            filename,
+            bot,
+        } => {
+            let client = bot.client()?;
+            let content = std::fs::read(&path)?;
+            let filename = match filename {
+                Some(filename) => filename,
+                None => path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .ok_or("ファイル名を指定してください")?,
+            };
+            client
+                .upload_file(vec![channel], content, &filename)
+                .await?;
+        }
+        Command::Serve { config } => {
+            let config = SlackConfig::load(config.as_deref())?;
+            let app = SlackApp::from_config(LoggingHandler, &config);
+            if config.socket_mode {
+                let app_token = config
+                    .app_token
+                    .as_ref()
+                    .ok_or("SLACK_APP_TOKENが設定されていません")?;
+                return app.run_socket_mode(app_token.expose_secret()).await;
+            }
+            let router = Router::new()
+                .route("/health", get(|| async { "OK" }))
+                .merge(app.try_into_router()?);
+            info!(
+                "サーバーを開始します: {}{}",
+                config.listen_addr, config.events_path
+            );
+            axum::Server::bind(&config.listen_addr)
+                .serve(router.into_make_service())
+                .with_graceful_shutdown(async {
+                    tokio::signal::ctrl_c().await.ok();
+                })
+                .await?;
+        }
+        Command::Socket { app_token } => {
+            let client = SocketModeClient::new(&app_token)?;
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+            let print = async move {
+                while let Some(event) = receiver.recv().await {
+                    match serde_json::to_string(&event) {
+                        Ok(line) => println!("{}", line),
+                        Err(e) => tracing::warn!("イベントをJSONに変換できません: {}", e),
+                    }
+                }
+            };
+            tokio::select! {
+                result = client.listen(sender) => result?,
+                _ = print => {}
+                _ = tokio::signal::ctrl_c() => info!("終了します"),
+            }
+        }
+        Command::VerifySignature {
+            timestamp,
+            signature,
+            body,
+            signing_secret,
+            max_age,
+        } => {
+            let body = read_input(body.as_deref())?;
+            if let Some(max_age) = max_age {
+                let sent_at: u64 = timestamp.parse()?;
+                let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                if now.abs_diff(sent_at) > max_age {
+                    return Err(format!(
+                        "タイムスタンプが{}秒以上ずれています: {}",
+                        max_age, timestamp
+                    )
+                    .into());
+                }
+            }
+            let verifier =
+                SlackEventSignatureVerifier::new(&SigningSecret::new(signing_secret).into());
+            verifier
+                .verify(&signature, &body, &timestamp)
+                .map_err(|e| format!("署名が一致しません: {}", e))?;
+            println!("署名は有効です");
+        }
+        Command::AuthTest { bot } => {
+            let info = bot.client()?.auth_test().await?;
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        }
+    }
+    Ok(())
+}
 
-    // 環境変数からSlack署名シークレットを取得
-    let signing_secret =
-        std::env::var("SLACK_SIGNING_SECRET").expect("SLACK_SIGNING_SECRETが設定されていません");
+/// ファイル、またはファイルが指定されていない（`-`の）場合は標準入力から読み込みます
+fn read_input(path: Option<&Path>) -> Result<String, Box<dyn Error>> {
+    match path {
+        Some(path) if path != Path::new("-") => Ok(std::fs::read_to_string(path)?),
+        _ => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
 
-    // ルーターの設定
-    let router = Router::new()
-        .route("/health", get(|| async { "OK" }))
-        .merge(create_app(SigningSecret::new(signing_secret)));
+/// ブロックの配列、または`{"blocks": [...]}`（Block Kit Builderの形式）を読み込みます
+fn parse_blocks(json: &str) -> Result<Vec<Block>, Box<dyn Error>> {
+    let mut value: serde_json::Value = serde_json::from_str(json)?;
+    if let Some(blocks) = value.get_mut("blocks") {
+        value = blocks.take();
+    }
+    Ok(serde_json::from_value(value)?)
+}
 
-    // サーバーアドレスの設定
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    info!("サーバーを開始します: {}", addr);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // サーバーの起動
-    axum::Server::bind(&addr)
-        .serve(router.into_make_service())
-        .await
-        .unwrap();
+    #[test]
+    fn test_parse_blocks() {
+        let array = r#"[{"type": "divider"}]"#;
+        let builder = r#"{"blocks": [{"type": "divider"}, {"type": "divider"}]}"#;
+        assert_eq!(parse_blocks(array).unwrap().len(), 1);
+        assert_eq!(parse_blocks(builder).unwrap().len(), 2);
+        assert!(parse_blocks("{}").is_err());
+    }
 }
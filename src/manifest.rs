@@ -0,0 +1,475 @@
+//! Slackアプリのマニフェストの生成
+//!
+//! 登録したハンドラ（[`SlackEventHandler::manifest`](crate::SlackEventHandler::manifest)）や
+//! 使用するAPIメソッドから、購読するイベントと必要なOAuthスコープを組み立てます。
+//! [`SlackApp::manifest`](crate::SlackApp::manifest)はさらに、受信するパスのリクエストURLも設定します。
+//! 生成したマニフェストはアプリ設定画面の「App Manifest」にJSONまたはYAMLで貼り付けられます。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Slackアプリのマニフェスト
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppManifest {
+    pub display_information: DisplayInformation,
+    #[serde(default)]
+    pub features: Features,
+    #[serde(default)]
+    pub oauth_config: OAuthConfig,
+    #[serde(default)]
+    pub settings: Settings,
+}
+
+/// アプリの表示情報
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplayInformation {
+    /// アプリ名（35文字以内）
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_description: Option<String>,
+    /// 背景色（`#2c2d30`の形式）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+}
+
+/// アプリの機能
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Features {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_user: Option<BotUser>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slash_commands: Vec<SlashCommand>,
+    /// リンクのプレビューを生成するドメイン
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub unfurl_domains: BTreeSet<String>,
+}
+
+/// ボットユーザー
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotUser {
+    pub display_name: String,
+    #[serde(default)]
+    pub always_online: bool,
+}
+
+/// スラッシュコマンド
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlashCommand {
+    /// コマンド（`/deploy`）
+    pub command: String,
+    pub description: String,
+    /// HTTPで受信する場合のリクエストURL（Socket Modeでは不要）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 引数の例（`[環境] [バージョン]`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_hint: Option<String>,
+    /// チャンネルやユーザーを`<#C123|general>`の形式で受け取るか
+    #[serde(default)]
+    pub should_escape: bool,
+}
+
+impl SlashCommand {
+    pub fn new(command: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            description: description.into(),
+            url: None,
+            usage_hint: None,
+            should_escape: false,
+        }
+    }
+
+    pub fn with_usage_hint(mut self, usage_hint: impl Into<String>) -> Self {
+        self.usage_hint = Some(usage_hint.into());
+        self
+    }
+}
+
+/// OAuthの設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OAuthConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_urls: Vec<String>,
+    #[serde(default)]
+    pub scopes: Scopes,
+}
+
+/// OAuthスコープ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scopes {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub bot: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub user: BTreeSet<String>,
+}
+
+/// イベントやインタラクションの受信設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_subscriptions: Option<EventSubscriptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interactivity: Option<Interactivity>,
+    #[serde(default)]
+    pub socket_mode_enabled: bool,
+    #[serde(default)]
+    pub org_deploy_enabled: bool,
+    #[serde(default)]
+    pub token_rotation_enabled: bool,
+}
+
+/// 購読するイベント
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventSubscriptions {
+    /// HTTPで受信する場合のリクエストURL（Socket Modeでは不要）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_url: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub bot_events: BTreeSet<String>,
+}
+
+/// ボタンやモーダルなどのインタラクションの受信設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Interactivity {
+    pub is_enabled: bool,
+    /// HTTPで受信する場合のリクエストURL（Socket Modeでは不要）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_url: Option<String>,
+}
+
+impl AppManifest {
+    /// アプリ名を指定してマニフェストを作成します
+    ///
+    /// アプリ名をボットユーザーの表示名にも使用します。
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            features: Features {
+                bot_user: Some(BotUser {
+                    display_name: name.clone(),
+                    always_online: false,
+                }),
+                ..Default::default()
+            },
+            display_information: DisplayInformation {
+                name,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.display_information.description = Some(description.into());
+        self
+    }
+
+    pub fn with_background_color(mut self, color: impl Into<String>) -> Self {
+        self.display_information.background_color = Some(color.into());
+        self
+    }
+
+    /// ボットイベントを購読し、必要なスコープを追加します
+    pub fn with_bot_event(mut self, event: &str) -> Self {
+        self.settings
+            .event_subscriptions
+            .get_or_insert_with(Default::default)
+            .bot_events
+            .insert(event.to_string());
+        self.with_bot_scopes(scopes_for_event(event))
+    }
+
+    /// スラッシュコマンドを追加します
+    pub fn with_command(mut self, command: SlashCommand) -> Self {
+        self.features
+            .slash_commands
+            .retain(|c| c.command != command.command);
+        self.features.slash_commands.push(command);
+        self.with_bot_scopes(&["commands"])
+    }
+
+    /// APIメソッド（`chat.postMessage`など）を使用するのに必要なボットスコープを追加します
+    ///
+    /// 対応表にないメソッドは無視します。
+    pub fn with_api_method(self, method: &str) -> Self {
+        self.with_bot_scopes(scopes_for_method(method))
+    }
+
+    /// クライアントがこれまでに呼び出したAPIメソッドに必要なボットスコープを追加します
+    ///
+    /// 開発中にボットを動かした後などに、[`with_api_method`](Self::with_api_method)を
+    /// 一つずつ呼び出す代わりに使います。
+    #[cfg(feature = "message")]
+    pub fn with_client(self, client: &crate::MessageClient) -> Self {
+        client
+            .api_methods()
+            .iter()
+            .fold(self, |manifest, method| manifest.with_api_method(method))
+    }
+
+    pub fn with_bot_scopes(mut self, scopes: &[&str]) -> Self {
+        let bot = &mut self.oauth_config.scopes.bot;
+        bot.extend(scopes.iter().map(|s| s.to_string()));
+        self
+    }
+
+    pub fn with_user_scopes(mut self, scopes: &[&str]) -> Self {
+        let user = &mut self.oauth_config.scopes.user;
+        user.extend(scopes.iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// リンクのプレビューを生成するドメインを追加します
+    pub fn with_unfurl_domain(mut self, domain: impl Into<String>) -> Self {
+        self.features.unfurl_domains.insert(domain.into());
+        self
+    }
+
+    /// ボタンやモーダルなどのインタラクションを有効にします
+    pub fn with_interactivity(mut self) -> Self {
+        self.settings
+            .interactivity
+            .get_or_insert_with(Default::default)
+            .is_enabled = true;
+        self
+    }
+
+    /// Socket Modeで受信するかを設定します
+    pub fn with_socket_mode(mut self, enabled: bool) -> Self {
+        self.settings.socket_mode_enabled = enabled;
+        self
+    }
+
+    /// HTTPで受信する場合のリクエストURLを設定します
+    ///
+    /// `base_url`にイベント・インタラクション・スラッシュコマンドのパスをそれぞれ付け加えます。
+    pub fn with_request_urls(
+        mut self,
+        base_url: &str,
+        events_path: &str,
+        interactions_path: &str,
+        commands_path: &str,
+    ) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        if let Some(events) = &mut self.settings.event_subscriptions {
+            events.request_url = Some(format!("{}{}", base_url, events_path));
+        }
+        if let Some(interactivity) = &mut self.settings.interactivity {
+            interactivity.request_url = Some(format!("{}{}", base_url, interactions_path));
+        }
+        for command in &mut self.features.slash_commands {
+            command.url = Some(format!("{}{}", base_url, commands_path));
+        }
+        self
+    }
+
+    /// ハンドラが必要とするイベントやスコープを追加します
+    #[cfg(feature = "events")]
+    pub fn with_handler<H: crate::SlackEventHandler>(self, handler: &H) -> Self {
+        handler.manifest(self)
+    }
+
+    /// JSONに変換します
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("マニフェストのJSON変換に失敗しました")
+    }
+
+    /// YAMLに変換します
+    pub fn to_yaml(&self) -> String {
+        let value = serde_json::to_value(self).expect("マニフェストのJSON変換に失敗しました");
+        let mut yaml = String::new();
+        write_yaml(&mut yaml, &value, 0);
+        yaml
+    }
+}
+
+/// JSONの値をYAMLのブロック形式で書き出します
+///
+/// 文字列はJSONと同じ形式でクォートするため、エスケープもYAMLとしてそのまま有効です。
+fn write_yaml(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::Object(m) if !m.is_empty() => {
+                        writeln!(out, "{}{}:", pad, key).ok();
+                        write_yaml(out, value, indent + 2);
+                    }
+                    Value::Array(a) if !a.is_empty() => {
+                        writeln!(out, "{}{}:", pad, key).ok();
+                        write_yaml(out, value, indent);
+                    }
+                    _ => {
+                        writeln!(out, "{}{}: {}", pad, key, scalar(value)).ok();
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                match item {
+                    Value::Object(m) if !m.is_empty() => {
+                        // 最初のキーを`- `と同じ行に書く
+                        let mut nested = String::new();
+                        write_yaml(&mut nested, item, indent + 2);
+                        let first = nested.trim_start();
+                        writeln!(out, "{}- {}", pad, first.trim_end_matches('\n')).ok();
+                    }
+                    _ => {
+                        writeln!(out, "{}- {}", pad, scalar(item)).ok();
+                    }
+                }
+            }
+        }
+        _ => {
+            writeln!(out, "{}{}", pad, scalar(value)).ok();
+        }
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// イベントの購読に必要なボットスコープ
+pub fn scopes_for_event(event: &str) -> &'static [&'static str] {
+    match event {
+        "app_mention" => &["app_mentions:read"],
+        "message.channels" => &["channels:history"],
+        "message.groups" => &["groups:history"],
+        "message.im" => &["im:history"],
+        "message.mpim" => &["mpim:history"],
+        "link_shared" => &["links:read"],
+        "message_metadata_posted" | "message_metadata_updated" | "message_metadata_deleted" => {
+            &["metadata.message:read"]
+        }
+        "reaction_added" | "reaction_removed" => &["reactions:read"],
+        "member_joined_channel" | "member_left_channel" | "channel_created" => &["channels:read"],
+        "team_join" | "user_change" => &["users:read"],
+        "pin_added" | "pin_removed" => &["pins:read"],
+        _ => &[],
+    }
+}
+
+/// APIメソッドの呼び出しに必要なボットスコープ
+///
+/// 会話の種類ごとにスコープが分かれているメソッドは、すべての種類のスコープを返します。
+pub fn scopes_for_method(method: &str) -> &'static [&'static str] {
+    match method {
+        "chat.postMessage" | "chat.update" | "chat.delete" | "chat.postEphemeral" => {
+            &["chat:write"]
+        }
+        "chat.unfurl" => &["links:write"],
+        "files.upload" | "files.uploadV2" => &["files:write"],
+        "reactions.add" | "reactions.remove" => &["reactions:write"],
+        "reactions.get" | "reactions.list" => &["reactions:read"],
+        "conversations.history" | "conversations.replies" => &[
+            "channels:history",
+            "groups:history",
+            "im:history",
+            "mpim:history",
+        ],
+        "conversations.info" | "conversations.list" | "conversations.members" => {
+            &["channels:read", "groups:read", "im:read", "mpim:read"]
+        }
+        "conversations.create"
+        | "conversations.invite"
+        | "conversations.kick"
+        | "conversations.leave"
+        | "conversations.archive"
+        | "conversations.unarchive"
+        | "conversations.rename"
+        | "conversations.setTopic"
+        | "conversations.setPurpose" => &["channels:manage", "groups:write"],
+        "conversations.join" => &["channels:join"],
+        "users.info" | "users.list" | "users.getPresence" => &["users:read"],
+        "users.lookupByEmail" => &["users:read.email"],
+        "users.profile.get" => &["users.profile:read"],
+        "pins.add" | "pins.remove" => &["pins:write"],
+        "pins.list" => &["pins:read"],
+        "bookmarks.add" | "bookmarks.edit" | "bookmarks.remove" => &["bookmarks:write"],
+        "bookmarks.list" => &["bookmarks:read"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> AppManifest {
+        AppManifest::new("デプロイBot")
+            .with_description("デプロイを実行します")
+            .with_bot_event("app_mention")
+            .with_command(SlashCommand::new("/deploy", "デプロイを実行").with_usage_hint("[環境]"))
+            .with_api_method("chat.postMessage")
+            .with_api_method("chat.update")
+            .with_interactivity()
+            .with_request_urls(
+                "https://example.com/",
+                "/push",
+                "/interactions",
+                "/commands",
+            )
+    }
+
+    #[test]
+    fn test_infers_scopes() {
+        let manifest = manifest();
+        let bot: Vec<&str> = manifest
+            .oauth_config
+            .scopes
+            .bot
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(bot, vec!["app_mentions:read", "chat:write", "commands"]);
+        assert_eq!(
+            manifest.features.slash_commands[0].url.as_deref(),
+            Some("https://example.com/commands")
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let manifest = manifest();
+        let json: Value = serde_json::from_str(&manifest.to_json()).unwrap();
+        assert_eq!(
+            json["settings"]["event_subscriptions"]["request_url"],
+            "https://example.com/push"
+        );
+        assert_eq!(
+            json["settings"]["event_subscriptions"]["bot_events"][0],
+            "app_mention"
+        );
+        assert!(json["display_information"]
+            .get("long_description")
+            .is_none());
+        assert_eq!(
+            serde_json::from_value::<AppManifest>(json).unwrap(),
+            manifest
+        );
+    }
+
+    #[test]
+    fn test_yaml() {
+        let yaml = AppManifest::new("Bot")
+            .with_command(SlashCommand::new("/deploy", "デプロイ"))
+            .to_yaml();
+        assert!(yaml.starts_with("display_information:\n  name: \"Bot\"\n"));
+        assert!(yaml.contains(
+            "  slash_commands:\n  - command: \"/deploy\"\n    description: \"デプロイ\"\n    should_escape: false\n"
+        ));
+        assert!(yaml.contains("  scopes:\n    bot:\n    - \"commands\"\n"));
+    }
+}
@@ -0,0 +1,1018 @@
+//! CommonMarkをSlackのmrkdwnやBlock Kitに変換するモジュール
+//!
+//! LLMの出力やGitHubのリリースノートなど、CommonMarkで書かれた文章を
+//! Slackで表示できる形式に変換します。
+//!
+//! - [`to_mrkdwn`]: mrkdwn形式の文字列に変換します
+//! - [`to_blocks`]: 見出しやコードブロック、リストを対応するブロックに変換します
+//!
+//! mrkdwnには見出しや表がないため、見出しは太字、表はコードブロックとして表現します。
+
+use crate::blocks::{
+    Block, HeaderBlock, ImageBlock, RichTextBlock, RichTextElement, RichTextInline, RichTextLink,
+    RichTextList, RichTextSection, RichTextStyle, RichTextText, SectionBlock, TextObject,
+    MAX_HEADER_TEXT_LENGTH, MAX_SECTION_FIELDS, MAX_SECTION_TEXT_LENGTH,
+};
+use crate::mrkdwn::{self, escape, escape_url};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// CommonMarkをmrkdwn形式の文字列に変換します。
+pub fn to_mrkdwn(markdown: &str) -> String {
+    render_nodes(&parse(markdown), 0)
+}
+
+/// CommonMarkをブロックの並びに変換します。
+///
+/// - 見出し → ヘッダーブロック
+/// - 段落 → mrkdwnのセクションブロック
+/// - コードブロック → 整形済みテキストのリッチテキストブロック
+/// - リスト・引用 → リッチテキストブロック
+/// - 表 → 2列以下ならセクションのフィールド、それ以外は整形済みテキスト
+/// - 水平線 → 区切り線
+///
+/// ブロックごとの文字数制限を超える場合は、段落や行の区切りで複数のブロックに分割します。
+pub fn to_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraphs = String::new();
+
+    for node in parse(markdown) {
+        if let Node::Paragraph(inlines) = &node {
+            if let [Inline::Image { url, alt }] = inlines.as_slice() {
+                flush_paragraphs(&mut paragraphs, &mut blocks);
+                blocks.push(Block::Image(ImageBlock {
+                    block_id: None,
+                    image_url: url.clone(),
+                    alt_text: if alt.is_empty() {
+                        url.clone()
+                    } else {
+                        alt.clone()
+                    },
+                    title: None,
+                }));
+            } else {
+                if !paragraphs.is_empty() {
+                    paragraphs.push_str("\n\n");
+                }
+                paragraphs.push_str(&render_inlines(inlines));
+            }
+            continue;
+        }
+
+        flush_paragraphs(&mut paragraphs, &mut blocks);
+        match node {
+            Node::Paragraph(_) => unreachable!(),
+            Node::Heading(inlines) => {
+                let text = plain_text(&inlines);
+                if text.chars().count() <= MAX_HEADER_TEXT_LENGTH {
+                    blocks.push(Block::Header(HeaderBlock {
+                        block_id: None,
+                        text: TextObject::plain(text),
+                    }));
+                } else {
+                    paragraphs.push_str(&render_heading(&inlines));
+                    flush_paragraphs(&mut paragraphs, &mut blocks);
+                }
+            }
+            Node::Code(code) => {
+                for chunk in split_text(&code, MAX_SECTION_TEXT_LENGTH) {
+                    blocks.push(rich_text(vec![RichTextElement::Preformatted(
+                        RichTextSection {
+                            elements: vec![text_inline(chunk, None)],
+                            border: None,
+                        },
+                    )]));
+                }
+            }
+            Node::List { start, items } => {
+                let mut elements = Vec::new();
+                list_elements(start, &items, 0, &mut elements);
+                blocks.extend(list_blocks(elements));
+            }
+            Node::Quote(children) => {
+                let mut elements = Vec::new();
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        elements.push(text_inline("\n".to_string(), None));
+                    }
+                    match child {
+                        Node::Paragraph(inlines) => elements.extend(rich_inlines(inlines)),
+                        other => elements.push(text_inline(render_node(other, 0), None)),
+                    }
+                }
+                for elements in split_inlines(elements, MAX_SECTION_TEXT_LENGTH) {
+                    blocks.push(rich_text(vec![RichTextElement::Quote(RichTextSection {
+                        elements,
+                        border: None,
+                    })]));
+                }
+            }
+            Node::Rule => blocks.push(Block::divider()),
+            Node::Table(rows) => blocks.extend(table_blocks(&rows)),
+        }
+    }
+    flush_paragraphs(&mut paragraphs, &mut blocks);
+    blocks
+}
+
+/// 段落などのブロック要素
+#[derive(Debug)]
+enum Node {
+    Paragraph(Vec<Inline>),
+    Heading(Vec<Inline>),
+    Code(String),
+    List {
+        start: Option<u64>,
+        items: Vec<Vec<Node>>,
+    },
+    Quote(Vec<Node>),
+    Rule,
+    /// 表（先頭行はヘッダー）
+    Table(Vec<Vec<Vec<Inline>>>),
+}
+
+/// インライン要素
+#[derive(Debug, Clone)]
+enum Inline {
+    Span(Span),
+    Break,
+    Image { url: String, alt: String },
+}
+
+/// 同じ装飾を持つテキストの断片
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Span {
+    text: String,
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+impl Span {
+    fn same_format(&self, other: &Span) -> bool {
+        self.bold == other.bold
+            && self.italic == other.italic
+            && self.strike == other.strike
+            && self.code == other.code
+            && self.link == other.link
+    }
+}
+
+fn parse(markdown: &str) -> Vec<Node> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut events = Parser::new_ext(markdown, options);
+    parse_nodes(&mut events)
+}
+
+/// 対応する終了タグ（またはイベントの終端）までのブロック要素を読み取ります。
+fn parse_nodes<'a>(events: &mut impl Iterator<Item = Event<'a>>) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut inlines = InlineBuilder::default();
+
+    while let Some(event) = events.next() {
+        let node = match event {
+            Event::Start(Tag::Paragraph) => Node::Paragraph(parse_inlines(events)),
+            Event::Start(Tag::Heading { .. }) => Node::Heading(parse_inlines(events)),
+            Event::Start(Tag::CodeBlock(_)) => {
+                let mut code = String::new();
+                for event in events.by_ref() {
+                    match event {
+                        Event::Text(text) => code.push_str(&text),
+                        Event::End(TagEnd::CodeBlock) => break,
+                        _ => {}
+                    }
+                }
+                code.truncate(code.trim_end_matches('\n').len());
+                Node::Code(code)
+            }
+            Event::Start(Tag::HtmlBlock) => {
+                let mut html = String::new();
+                for event in events.by_ref() {
+                    match event {
+                        Event::Html(text) | Event::Text(text) => html.push_str(&text),
+                        Event::End(TagEnd::HtmlBlock) => break,
+                        _ => {}
+                    }
+                }
+                Node::Paragraph(vec![Inline::Span(Span {
+                    text: html.trim_end().to_string(),
+                    ..Default::default()
+                })])
+            }
+            Event::Start(Tag::List(start)) => {
+                let mut items = Vec::new();
+                while let Some(event) = events.next() {
+                    match event {
+                        Event::Start(Tag::Item) => items.push(parse_nodes(events)),
+                        Event::End(_) => break,
+                        _ => {}
+                    }
+                }
+                Node::List { start, items }
+            }
+            Event::Start(Tag::BlockQuote(_)) => Node::Quote(parse_nodes(events)),
+            Event::Start(Tag::Table(_)) => {
+                let mut rows = Vec::new();
+                while let Some(event) = events.next() {
+                    match event {
+                        Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => {
+                            let mut cells = Vec::new();
+                            while let Some(event) = events.next() {
+                                match event {
+                                    Event::Start(Tag::TableCell) => {
+                                        cells.push(parse_inlines(events))
+                                    }
+                                    Event::End(_) => break,
+                                    _ => {}
+                                }
+                            }
+                            rows.push(cells);
+                        }
+                        Event::End(_) => break,
+                        _ => {}
+                    }
+                }
+                Node::Table(rows)
+            }
+            Event::Rule => Node::Rule,
+            Event::End(_) => break,
+            // 段落で囲まれていないインライン要素（タイトなリストの項目など）
+            other => {
+                inlines.push(other);
+                continue;
+            }
+        };
+        inlines.flush_into(&mut nodes);
+        nodes.push(node);
+    }
+    inlines.flush_into(&mut nodes);
+    nodes
+}
+
+/// 対応する終了タグまでのインライン要素を読み取ります。
+fn parse_inlines<'a>(events: &mut impl Iterator<Item = Event<'a>>) -> Vec<Inline> {
+    let mut builder = InlineBuilder::default();
+    for event in events.by_ref() {
+        match event {
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::TableCell) => break,
+            other => builder.push(other),
+        }
+    }
+    builder.inlines
+}
+
+#[derive(Default)]
+struct InlineBuilder {
+    inlines: Vec<Inline>,
+    bold: u32,
+    italic: u32,
+    strike: u32,
+    link: Option<String>,
+    image: Option<(String, String)>,
+}
+
+impl InlineBuilder {
+    fn push(&mut self, event: Event<'_>) {
+        match event {
+            Event::Text(text) => self.text(&text, false),
+            Event::Code(code) | Event::InlineMath(code) | Event::DisplayMath(code) => {
+                self.text(&code, true)
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.text(&html, false),
+            Event::FootnoteReference(name) => self.text(&format!("[{}]", name), false),
+            Event::SoftBreak => self.text(" ", false),
+            Event::HardBreak => self.inlines.push(Inline::Break),
+            Event::TaskListMarker(checked) => self.text(if checked { "☑ " } else { "☐ " }, false),
+            Event::Start(Tag::Strong) => self.bold += 1,
+            Event::End(TagEnd::Strong) => self.bold = self.bold.saturating_sub(1),
+            Event::Start(Tag::Emphasis) => self.italic += 1,
+            Event::End(TagEnd::Emphasis) => self.italic = self.italic.saturating_sub(1),
+            Event::Start(Tag::Strikethrough) => self.strike += 1,
+            Event::End(TagEnd::Strikethrough) => self.strike = self.strike.saturating_sub(1),
+            Event::Start(Tag::Link { dest_url, .. }) => self.link = Some(dest_url.to_string()),
+            Event::End(TagEnd::Link) => self.link = None,
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.image = Some((dest_url.to_string(), String::new()))
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((url, alt)) = self.image.take() {
+                    self.inlines.push(Inline::Image { url, alt });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str, code: bool) {
+        if let Some((_, alt)) = &mut self.image {
+            alt.push_str(text);
+            return;
+        }
+        let span = Span {
+            text: text.to_string(),
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            strike: self.strike > 0,
+            code,
+            link: self.link.clone(),
+        };
+        match self.inlines.last_mut() {
+            Some(Inline::Span(last)) if last.same_format(&span) => last.text.push_str(text),
+            _ => self.inlines.push(Inline::Span(span)),
+        }
+    }
+
+    fn flush_into(&mut self, nodes: &mut Vec<Node>) {
+        if !self.inlines.is_empty() {
+            nodes.push(Node::Paragraph(std::mem::take(&mut self.inlines)));
+        }
+    }
+}
+
+fn render_nodes(nodes: &[Node], depth: usize) -> String {
+    nodes
+        .iter()
+        .map(|node| render_node(node, depth))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn render_node(node: &Node, depth: usize) -> String {
+    match node {
+        Node::Paragraph(inlines) => render_inlines(inlines),
+        Node::Heading(inlines) => render_heading(inlines),
+        Node::Code(code) => format!("```\n{}\n```", escape(code)),
+        Node::List { start, items } => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let marker = match start {
+                    Some(start) => format!("{}.", start + i as u64),
+                    None => ["•", "◦", "▪"][depth % 3].to_string(),
+                };
+                let body = item
+                    .iter()
+                    .map(|node| render_node(node, depth + 1))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let mut lines = body.lines();
+                let mut rendered = format!("{} {}", marker, lines.next().unwrap_or_default());
+                for line in lines {
+                    rendered.push_str("\n    ");
+                    rendered.push_str(line);
+                }
+                rendered
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Node::Quote(children) => render_nodes(children, depth)
+            .lines()
+            .map(|line| format!("> {}", line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Node::Rule => "───".to_string(),
+        Node::Table(rows) => format!("```\n{}\n```", escape(&table_text(rows))),
+    }
+}
+
+/// 見出しは全体を太字にして表現します。
+fn render_heading(inlines: &[Inline]) -> String {
+    let inlines: Vec<Inline> = inlines
+        .iter()
+        .cloned()
+        .map(|inline| match inline {
+            Inline::Span(span) => Inline::Span(Span { bold: true, ..span }),
+            other => other,
+        })
+        .collect();
+    render_inlines(&inlines)
+}
+
+fn render_inlines(inlines: &[Inline]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < inlines.len() {
+        match &inlines[i] {
+            Inline::Span(span) if span.link.is_some() => {
+                // 同じリンク先の断片はまとめて1つのリンクにする
+                let url = span.link.clone().unwrap_or_default();
+                let mut label = String::new();
+                while let Some(Inline::Span(s)) = inlines.get(i) {
+                    if s.link.as_deref() != Some(url.as_str()) {
+                        break;
+                    }
+                    label.push_str(&s.text);
+                    i += 1;
+                }
+                let link = if label.is_empty() || label == url {
+                    format!("<{}>", escape_url(&url))
+                } else {
+                    format!("<{}|{}>", escape_url(&url), escape(&label))
+                };
+                out.push_str(&wrap(&link, span));
+                continue;
+            }
+            Inline::Span(span) if span.code => {
+                out.push_str(&wrap(&format!("`{}`", escape(&span.text)), span))
+            }
+            Inline::Span(span) => out.push_str(&wrap(&escape(&span.text), span)),
+            Inline::Break => out.push('\n'),
+            Inline::Image { url, alt } if alt.is_empty() => {
+                out.push_str(&format!("<{}>", escape_url(url)))
+            }
+            Inline::Image { url, alt } => {
+                out.push_str(&format!("<{}|{}>", escape_url(url), escape(alt)))
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// 装飾記号で囲みます。Slackは記号の内側に空白があると装飾しないため、前後の空白は外に出します。
+fn wrap(text: &str, span: &Span) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+    let mut inner = trimmed.to_string();
+    for (enabled, marker) in [(span.strike, "~"), (span.italic, "_"), (span.bold, "*")] {
+        if enabled {
+            inner = format!("{}{}{}", marker, inner, marker);
+        }
+    }
+    format!("{}{}{}", &text[..start], inner, &text[end..])
+}
+
+fn plain_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Span(span) => span.text.clone(),
+            Inline::Break => "\n".to_string(),
+            Inline::Image { alt, .. } => alt.clone(),
+        })
+        .collect()
+}
+
+fn table_text(rows: &[Vec<Vec<Inline>>]) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| plain_text(cell)).collect())
+        .collect();
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(c))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |row: &Vec<String>| {
+        (0..columns)
+            .map(|c| {
+                let cell = row.get(c).map(String::as_str).unwrap_or("");
+                let padding = widths[c] - cell.chars().count();
+                format!("{}{}", cell, " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = Vec::new();
+    for (i, row) in cells.iter().enumerate() {
+        lines.push(format_row(row));
+        if i == 0 {
+            lines.push(
+                widths
+                    .iter()
+                    .map(|w| "-".repeat(*w))
+                    .collect::<Vec<_>>()
+                    .join("-+-"),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+fn table_blocks(rows: &[Vec<Vec<Inline>>]) -> Vec<Block> {
+    let two_columns = rows.iter().all(|row| row.len() <= 2);
+    if two_columns && rows.len() * 2 <= MAX_SECTION_FIELDS {
+        let fields = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                (0..2).map(move |c| {
+                    let cell = row.get(c).map(Vec::as_slice).unwrap_or(&[]);
+                    let text = if i == 0 {
+                        render_heading(cell)
+                    } else {
+                        render_inlines(cell)
+                    };
+                    // 空のフィールドはSlackに拒否されるため空白を入れる
+                    TextObject::mrkdwn(if text.is_empty() {
+                        " ".to_string()
+                    } else {
+                        text
+                    })
+                })
+            })
+            .collect();
+        return vec![Block::Section(SectionBlock {
+            fields: Some(fields),
+            ..Default::default()
+        })];
+    }
+
+    split_text(&table_text(rows), MAX_SECTION_TEXT_LENGTH)
+        .into_iter()
+        .map(|chunk| {
+            rich_text(vec![RichTextElement::Preformatted(RichTextSection {
+                elements: vec![text_inline(chunk, None)],
+                border: None,
+            })])
+        })
+        .collect()
+}
+
+fn flush_paragraphs(paragraphs: &mut String, blocks: &mut Vec<Block>) {
+    if paragraphs.is_empty() {
+        return;
+    }
+    for chunk in mrkdwn::split(paragraphs, MAX_SECTION_TEXT_LENGTH) {
+        blocks.push(Block::section(TextObject::mrkdwn(chunk)));
+    }
+    paragraphs.clear();
+}
+
+/// リストをリッチテキストのリスト要素に変換します。
+///
+/// Slackのリストは入れ子を持てないため、入れ子のリストはインデントを深くした
+/// 別のリスト要素として並べます。
+fn list_elements(
+    start: Option<u64>,
+    items: &[Vec<Node>],
+    indent: u32,
+    out: &mut Vec<RichTextElement>,
+) {
+    let style = if start.is_some() { "ordered" } else { "bullet" };
+    let first = start.unwrap_or(1).saturating_sub(1) as u32;
+    let mut run: Vec<RichTextElement> = Vec::new();
+    let mut offset = first;
+
+    let flush = |run: &mut Vec<RichTextElement>, offset: u32, out: &mut Vec<RichTextElement>| {
+        if run.is_empty() {
+            return;
+        }
+        out.push(RichTextElement::List(RichTextList {
+            style: style.to_string(),
+            elements: std::mem::take(run),
+            indent: Some(indent),
+            offset: (offset > 0).then_some(offset),
+            border: None,
+        }));
+    };
+
+    for (i, item) in items.iter().enumerate() {
+        let mut elements = Vec::new();
+        let mut nested = Vec::new();
+        for node in item {
+            match node {
+                Node::Paragraph(inlines) | Node::Heading(inlines) => {
+                    if !elements.is_empty() {
+                        elements.push(text_inline("\n".to_string(), None));
+                    }
+                    elements.extend(rich_inlines(inlines));
+                }
+                other => nested.push(other),
+            }
+        }
+        run.push(RichTextElement::Section(RichTextSection {
+            elements,
+            border: None,
+        }));
+
+        if nested.is_empty() {
+            continue;
+        }
+        flush(&mut run, offset, out);
+        offset = first + i as u32 + 1;
+        for node in nested {
+            match node {
+                Node::List { start, items } => list_elements(*start, items, indent + 1, out),
+                Node::Code(code) => {
+                    for chunk in split_text(code, MAX_SECTION_TEXT_LENGTH) {
+                        out.push(RichTextElement::Preformatted(RichTextSection {
+                            elements: vec![text_inline(chunk, None)],
+                            border: None,
+                        }));
+                    }
+                }
+                other => {
+                    for chunk in split_text(&render_node(other, 0), MAX_SECTION_TEXT_LENGTH) {
+                        out.push(RichTextElement::Section(RichTextSection {
+                            elements: vec![text_inline(chunk, None)],
+                            border: None,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    flush(&mut run, offset, out);
+}
+
+fn rich_text(elements: Vec<RichTextElement>) -> Block {
+    Block::RichText(RichTextBlock {
+        block_id: None,
+        elements,
+    })
+}
+
+fn text_inline(text: String, style: Option<RichTextStyle>) -> RichTextInline {
+    RichTextInline::Text(RichTextText { text, style })
+}
+
+fn rich_inlines(inlines: &[Inline]) -> Vec<RichTextInline> {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Span(span) => {
+                let style =
+                    (span.bold || span.italic || span.strike || span.code).then(|| RichTextStyle {
+                        bold: span.bold.then_some(true),
+                        italic: span.italic.then_some(true),
+                        strike: span.strike.then_some(true),
+                        code: span.code.then_some(true),
+                    });
+                match &span.link {
+                    Some(url) => RichTextInline::Link(RichTextLink {
+                        url: url.clone(),
+                        text: Some(span.text.clone()),
+                        style,
+                    }),
+                    None => text_inline(span.text.clone(), style),
+                }
+            }
+            Inline::Break => text_inline("\n".to_string(), None),
+            Inline::Image { url, alt } => RichTextInline::Link(RichTextLink {
+                url: url.clone(),
+                text: (!alt.is_empty()).then(|| alt.clone()),
+                style: None,
+            }),
+        })
+        .collect()
+}
+
+/// 整形済みテキストを`limit`文字以内になるよう、段落・行・空白の順に区切りを探して分割します。
+///
+/// 取り除くのは分割に使った区切りだけで、コードや入れ子のリストの行頭のインデントはそのまま残します。
+fn split_text(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.chars().count() > limit {
+        let boundary = rest
+            .char_indices()
+            .nth(limit)
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let head = &rest[..boundary];
+        let (split, separator) = ["\n\n", "\n", " "]
+            .iter()
+            .find_map(|sep| head.rfind(sep).filter(|&i| i > 0).map(|i| (i, sep.len())))
+            .unwrap_or((boundary, 0));
+        chunks.push(rest[..split].to_string());
+        rest = &rest[split + separator..];
+    }
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+/// インライン要素を、1つあたりの文字数が`limit`以内になるよう分けます。
+///
+/// 上限を超えるテキストは[`split_text`]で分割し、装飾はそのまま引き継ぎます。リンクは分割しません。
+fn split_inlines(inlines: Vec<RichTextInline>, limit: usize) -> Vec<Vec<RichTextInline>> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut length = 0;
+    for inline in inlines {
+        let parts = match inline {
+            RichTextInline::Text(text) if text.text.chars().count() > limit => {
+                split_text(&text.text, limit)
+                    .into_iter()
+                    .map(|chunk| text_inline(chunk, text.style.clone()))
+                    .collect()
+            }
+            other => vec![other],
+        };
+        for part in parts {
+            let part_length = inline_len(&part);
+            if length + part_length > limit && !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+                length = 0;
+            }
+            current.push(part);
+            length += part_length;
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// リストの要素を、1ブロックあたりの文字数が[`MAX_SECTION_TEXT_LENGTH`]以内になるよう
+/// 複数のリッチテキストブロックに分けます。
+///
+/// 項目の途中では分割しないため、1項目だけで上限を超える場合はその項目だけのブロックになります。
+/// 番号付きリストは分割後も番号が続くよう`offset`を設定します。
+fn list_blocks(elements: Vec<RichTextElement>) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    let mut length = 0;
+    for element in elements {
+        let RichTextElement::List(list) = element else {
+            let element_length = rich_text_len(&element);
+            if length + element_length > MAX_SECTION_TEXT_LENGTH && !current.is_empty() {
+                blocks.push(rich_text(std::mem::take(&mut current)));
+                length = 0;
+            }
+            current.push(element);
+            length += element_length;
+            continue;
+        };
+
+        let RichTextList {
+            style,
+            elements: items,
+            indent,
+            offset,
+            border,
+        } = list;
+        let first = offset.unwrap_or(0);
+        let mut part = RichTextList {
+            style,
+            elements: Vec::new(),
+            indent,
+            offset,
+            border,
+        };
+        for (i, item) in items.into_iter().enumerate() {
+            let item_length = rich_text_len(&item);
+            if length + item_length > MAX_SECTION_TEXT_LENGTH
+                && (!current.is_empty() || !part.elements.is_empty())
+            {
+                if !part.elements.is_empty() {
+                    current.push(RichTextElement::List(RichTextList {
+                        elements: std::mem::take(&mut part.elements),
+                        ..part.clone()
+                    }));
+                }
+                blocks.push(rich_text(std::mem::take(&mut current)));
+                length = 0;
+                part.offset = Some(first + i as u32).filter(|&offset| offset > 0);
+            }
+            part.elements.push(item);
+            length += item_length;
+        }
+        if !part.elements.is_empty() {
+            current.push(RichTextElement::List(part));
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(rich_text(current));
+    }
+    blocks
+}
+
+/// リッチテキストの要素に含まれるテキストの文字数
+fn rich_text_len(element: &RichTextElement) -> usize {
+    match element {
+        RichTextElement::Section(section)
+        | RichTextElement::Preformatted(section)
+        | RichTextElement::Quote(section) => section.elements.iter().map(inline_len).sum(),
+        RichTextElement::List(list) => list.elements.iter().map(rich_text_len).sum(),
+        RichTextElement::Unknown(_) => 0,
+    }
+}
+
+/// インライン要素の文字数
+fn inline_len(inline: &RichTextInline) -> usize {
+    match inline {
+        RichTextInline::Text(text) => text.text.chars().count(),
+        RichTextInline::Link(link) => link.text.as_ref().unwrap_or(&link.url).chars().count(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_formatting_to_mrkdwn() {
+        let markdown =
+            "This is **bold**, *italic*, ~~strike~~ and `code` with a [link](https://example.com).";
+        assert_eq!(
+            to_mrkdwn(markdown),
+            "This is *bold*, _italic_, ~strike~ and `code` with a <https://example.com|link>."
+        );
+    }
+
+    #[test]
+    fn test_escapes_control_characters() {
+        assert_eq!(to_mrkdwn("a < b && c > d"), "a &lt; b &amp;&amp; c &gt; d");
+    }
+
+    #[test]
+    fn test_headings_lists_and_code_to_mrkdwn() {
+        let markdown =
+            "# Release 1.0\n\n- one\n- two\n  1. nested\n\n```rust\nfn main() {}\n```\n\n> quoted";
+        assert_eq!(
+            to_mrkdwn(markdown),
+            "*Release 1.0*\n\n• one\n• two\n    1. nested\n\n```\nfn main() {}\n```\n\n> quoted"
+        );
+    }
+
+    #[test]
+    fn test_table_to_mrkdwn_code_block() {
+        let markdown = "| a | bb |\n|---|---|\n| ccc | d |";
+        assert_eq!(to_mrkdwn(markdown), "```\na   | bb\n----+---\nccc | d\n```");
+    }
+
+    #[test]
+    fn test_to_blocks() {
+        let markdown = "# Title\n\nFirst paragraph.\n\nSecond **paragraph**.\n\n---\n\n```\ncargo test\n```\n\n- a\n- b";
+        let blocks = to_blocks(markdown);
+
+        assert!(matches!(&blocks[0], Block::Header(h) if h.text.text() == "Title"));
+        assert!(matches!(
+            &blocks[1],
+            Block::Section(s) if s.text.as_ref().unwrap().text() == "First paragraph.\n\nSecond *paragraph*."
+        ));
+        assert!(matches!(blocks[2], Block::Divider(_)));
+        let Block::RichText(code) = &blocks[3] else {
+            panic!("コードブロックがリッチテキストになっていません");
+        };
+        assert!(matches!(code.elements[0], RichTextElement::Preformatted(_)));
+        let Block::RichText(list) = &blocks[4] else {
+            panic!("リストがリッチテキストになっていません");
+        };
+        let RichTextElement::List(list) = &list.elements[0] else {
+            panic!("リスト要素がありません");
+        };
+        assert_eq!(list.style, "bullet");
+        assert_eq!(list.elements.len(), 2);
+    }
+
+    #[test]
+    fn test_nested_list_blocks_use_indent() {
+        let blocks = to_blocks("1. one\n   - child\n2. two");
+        let Block::RichText(block) = &blocks[0] else {
+            panic!("リストがリッチテキストになっていません");
+        };
+        let lists: Vec<(&str, Option<u32>, Option<u32>)> = block
+            .elements
+            .iter()
+            .map(|e| match e {
+                RichTextElement::List(l) => (l.style.as_str(), l.indent, l.offset),
+                _ => panic!("リスト以外の要素があります"),
+            })
+            .collect();
+        assert_eq!(
+            lists,
+            vec![
+                ("ordered", Some(0), None),
+                ("bullet", Some(1), None),
+                ("ordered", Some(0), Some(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_two_column_table_to_fields() {
+        let blocks = to_blocks("| key | value |\n|---|---|\n| env | prod |");
+        let Block::Section(section) = &blocks[0] else {
+            panic!("表がセクションになっていません");
+        };
+        let fields: Vec<&str> = section
+            .fields
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(TextObject::text)
+            .collect();
+        assert_eq!(fields, vec!["*key*", "*value*", "env", "prod"]);
+    }
+
+    #[test]
+    fn test_long_paragraphs_are_split() {
+        let paragraph = "word ".repeat(400);
+        let markdown = format!("{}\n\n{}", paragraph, paragraph);
+        let blocks = to_blocks(&markdown);
+        assert!(blocks.len() >= 2);
+        for block in &blocks {
+            let Block::Section(section) = block else {
+                panic!("段落がセクションになっていません");
+            };
+            assert!(
+                section.text.as_ref().unwrap().text().chars().count() <= MAX_SECTION_TEXT_LENGTH
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_quotes_and_list_code_are_split() {
+        let quote = format!("> **{}**", "word ".repeat(800).trim_end());
+        let code = format!(
+            "- item
+
+  ```
+{}
+  ```",
+            "  line
+"
+            .repeat(800)
+        );
+        for markdown in [quote, code] {
+            let blocks = to_blocks(&markdown);
+            assert!(blocks.len() >= 2, "{}", markdown);
+            for block in &blocks {
+                let Block::RichText(block) = block else {
+                    panic!("リッチテキストになっていません");
+                };
+                let length: usize = block.elements.iter().map(rich_text_len).sum();
+                assert!(length <= MAX_SECTION_TEXT_LENGTH);
+            }
+        }
+
+        // 分割しても引用の装飾は残る
+        let blocks = to_blocks(&format!("> **{}**", "word ".repeat(800).trim_end()));
+        let Block::RichText(block) = &blocks[1] else {
+            panic!("リッチテキストになっていません");
+        };
+        let [RichTextElement::Quote(quote)] = &block.elements[..] else {
+            panic!("引用になっていません");
+        };
+        assert!(matches!(
+            &quote.elements[0],
+            RichTextInline::Text(RichTextText { style: Some(style), .. }) if style.bold == Some(true)
+        ));
+    }
+
+    #[test]
+    fn test_link_urls_are_escaped() {
+        assert_eq!(
+            to_mrkdwn("[a](https://example.com/?q=a|b&c=<d>)"),
+            "<https://example.com/?q=a%7Cb&amp;c=&lt;d&gt;|a>"
+        );
+    }
+
+    #[test]
+    fn test_split_text_keeps_indentation() {
+        let code = "fn main() {\n    let a = 1;\n    let b = 2;\n}";
+        assert_eq!(
+            split_text(code, 20),
+            vec!["fn main() {", "    let a = 1;", "    let b = 2;\n}"]
+        );
+    }
+
+    #[test]
+    fn test_long_lists_are_split() {
+        let item = "x".repeat(100);
+        let markdown = (1..=50).fold(String::new(), |mut markdown, i| {
+            markdown.push_str(&format!("{}. {}\n", i, item));
+            markdown
+        });
+        let blocks = to_blocks(&markdown);
+        assert_eq!(blocks.len(), 2);
+
+        let lists: Vec<&RichTextList> = blocks
+            .iter()
+            .map(|block| match block {
+                Block::RichText(block) => match &block.elements[..] {
+                    [RichTextElement::List(list)] => list,
+                    _ => panic!("リスト以外の要素があります"),
+                },
+                _ => panic!("リストがリッチテキストになっていません"),
+            })
+            .collect();
+        assert_eq!(lists[0].elements.len(), 30);
+        assert_eq!(lists[0].offset, None);
+        // 2つ目のブロックは31番から始まる
+        assert_eq!(lists[1].elements.len(), 20);
+        assert_eq!(lists[1].offset, Some(30));
+    }
+}
@@ -0,0 +1,58 @@
+//! トークンの確認
+
+use super::MessageClient;
+use crate::types::{BotId, EnterpriseId, TeamId, UserId};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use tracing::info;
+
+/// `auth.test`で取得したトークンの持ち主の情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthInfo {
+    /// ワークスペースのURL
+    pub url: String,
+    /// ワークスペース名
+    pub team: String,
+    pub team_id: TeamId,
+    /// ユーザー名（ボットトークンではボットのユーザー名）
+    pub user: Option<String>,
+    pub user_id: UserId,
+    /// ボットID（ボットトークンの場合のみ）
+    pub bot_id: Option<BotId>,
+    /// Enterprise GridのID
+    pub enterprise_id: Option<EnterpriseId>,
+}
+
+impl MessageClient {
+    /// トークンが有効かを確認し、持ち主の情報を取得します
+    pub async fn auth_test(&self) -> Result<AuthInfo, Box<dyn Error>> {
+        let info: AuthInfo = self.api_get("auth.test", &Vec::new()).await?;
+        info!(
+            "トークンを確認しました: {} (user_id: {})",
+            info.team, info.user_id
+        );
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_auth_info() {
+        let info: AuthInfo = serde_json::from_value(serde_json::json!({
+            "ok": true,
+            "url": "https://example.slack.com/",
+            "team": "Example",
+            "user": "bot",
+            "team_id": "T1",
+            "user_id": "U1",
+            "bot_id": "B1",
+            "is_enterprise_install": false
+        }))
+        .unwrap();
+        assert_eq!(info.bot_id.unwrap(), "B1");
+        assert_eq!(info.enterprise_id, None);
+    }
+}
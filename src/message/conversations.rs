@@ -0,0 +1,423 @@
+//! チャンネルの作成・管理・取得
+
+use super::{
+    pagination::{paginate, ResponseMetadata},
+    MessageClient,
+};
+use crate::types::{ChannelId, TeamId, UserId};
+use futures::Stream;
+use serde::{de::IgnoredAny, Deserialize, Deserializer};
+use serde_json::json;
+use std::error::Error;
+use tracing::info;
+
+/// 1回のリクエストで取得する件数の既定値
+const DEFAULT_PAGE_SIZE: u32 = 200;
+
+/// チャンネル（パブリック・プライベート・DM・グループDM）
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Channel {
+    /// チャンネルID
+    pub id: ChannelId,
+    /// チャンネル名（DMでは`None`）
+    pub name: Option<String>,
+    #[serde(default)]
+    pub is_channel: bool,
+    #[serde(default)]
+    pub is_group: bool,
+    #[serde(default)]
+    pub is_im: bool,
+    #[serde(default)]
+    pub is_mpim: bool,
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub is_general: bool,
+    /// トークンのユーザー（ボット）が参加しているか
+    #[serde(default)]
+    pub is_member: bool,
+    /// 作成日時（UNIX時間）
+    pub created: Option<i64>,
+    /// 作成したユーザーのID
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub creator: Option<UserId>,
+    /// トピック
+    pub topic: Option<ChannelText>,
+    /// 説明
+    pub purpose: Option<ChannelText>,
+    /// メンバー数（`conversations.info`で`include_num_members`を指定した場合など）
+    pub num_members: Option<u32>,
+    /// DMの相手のユーザーID
+    pub user: Option<UserId>,
+}
+
+/// チャンネルのトピック・説明
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChannelText {
+    pub value: String,
+    /// 設定したユーザーのID（未設定の場合は`None`）
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub creator: Option<UserId>,
+    /// 設定日時（UNIX時間）
+    pub last_set: Option<i64>,
+}
+
+/// 未設定を空文字列で表すユーザーIDを`None`として読み込みます
+fn empty_as_none<'de, D>(deserializer: D) -> Result<Option<UserId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(id) if !id.is_empty() => id.parse().map(Some).map_err(serde::de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+/// [`MessageClient::list_channels`]で取得するチャンネルの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConversationType {
+    PublicChannel,
+    PrivateChannel,
+    Mpim,
+    Im,
+}
+
+impl ConversationType {
+    /// APIで指定する名前
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PublicChannel => "public_channel",
+            Self::PrivateChannel => "private_channel",
+            Self::Mpim => "mpim",
+            Self::Im => "im",
+        }
+    }
+}
+
+/// [`MessageClient::list_channels`]の絞り込み条件
+#[derive(Debug, Clone, Default)]
+pub struct ListChannelsOptions {
+    /// 取得する種類（空の場合はパブリックチャンネルのみ）
+    pub types: Vec<ConversationType>,
+    /// アーカイブされたチャンネルを除外するか
+    pub exclude_archived: bool,
+    /// Enterprise Gridでのワークスペースの指定
+    pub team_id: Option<TeamId>,
+    /// 1回のリクエストで取得する件数（既定値は200）
+    pub page_size: Option<u32>,
+}
+
+impl ListChannelsOptions {
+    fn params(&self) -> Vec<(&'static str, Option<String>)> {
+        let types = (!self.types.is_empty()).then(|| {
+            self.types
+                .iter()
+                .map(ConversationType::as_str)
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+        vec![
+            ("types", types),
+            (
+                "exclude_archived",
+                self.exclude_archived.then(|| "true".to_string()),
+            ),
+            ("team_id", self.team_id.as_ref().map(TeamId::to_string)),
+            (
+                "limit",
+                Some(self.page_size.unwrap_or(DEFAULT_PAGE_SIZE).to_string()),
+            ),
+        ]
+    }
+}
+
+#[derive(Deserialize)]
+struct ChannelResponse {
+    channel: Channel,
+}
+
+#[derive(Deserialize)]
+struct ListResponse {
+    #[serde(default)]
+    channels: Vec<Channel>,
+    response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Deserialize)]
+struct MembersResponse {
+    #[serde(default)]
+    members: Vec<UserId>,
+    response_metadata: Option<ResponseMetadata>,
+}
+
+impl MessageClient {
+    /// チャンネルを作成します
+    ///
+    /// # 引数
+    /// * `name` - チャンネル名（小文字・数字・ハイフン・アンダースコアのみ、80文字以内）
+    /// * `is_private` - プライベートチャンネルとして作成するか
+    pub async fn create_channel(
+        &self,
+        name: &str,
+        is_private: bool,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let res: ChannelResponse = self
+            .api_post(
+                "conversations.create",
+                &json!({ "name": name, "is_private": is_private }),
+            )
+            .await?;
+        info!("チャンネルを作成しました: {} ({})", name, res.channel.id);
+        Ok(res.channel)
+    }
+
+    /// チャンネルにユーザーを招待します
+    pub async fn invite_to_channel(
+        &self,
+        channel: impl Into<ChannelId>,
+        users: &[UserId],
+    ) -> Result<Channel, Box<dyn Error>> {
+        let channel = channel.into();
+        let users: Vec<&str> = users.iter().map(UserId::as_str).collect();
+        let res: ChannelResponse = self
+            .api_post(
+                "conversations.invite",
+                &json!({ "channel": channel, "users": users.join(",") }),
+            )
+            .await?;
+        info!(
+            "チャンネルに招待しました: {} ({})",
+            channel,
+            users.join(",")
+        );
+        Ok(res.channel)
+    }
+
+    /// チャンネルからユーザーを退出させます
+    pub async fn kick_from_channel(
+        &self,
+        channel: impl Into<ChannelId>,
+        user: impl Into<UserId>,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let user = user.into();
+        let _: IgnoredAny = self
+            .api_post(
+                "conversations.kick",
+                &json!({ "channel": channel, "user": user }),
+            )
+            .await?;
+        info!("チャンネルから退出させました: {} ({})", channel, user);
+        Ok(())
+    }
+
+    /// トークンのユーザー（ボット）がチャンネルに参加します
+    pub async fn join_channel(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let channel = channel.into();
+        let res: ChannelResponse = self
+            .api_post("conversations.join", &json!({ "channel": channel }))
+            .await?;
+        info!("チャンネルに参加しました: {}", channel);
+        Ok(res.channel)
+    }
+
+    /// トークンのユーザー（ボット）がチャンネルから退出します
+    pub async fn leave_channel(&self, channel: impl Into<ChannelId>) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post("conversations.leave", &json!({ "channel": channel }))
+            .await?;
+        info!("チャンネルから退出しました: {}", channel);
+        Ok(())
+    }
+
+    /// チャンネルをアーカイブします
+    pub async fn archive_channel(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post("conversations.archive", &json!({ "channel": channel }))
+            .await?;
+        info!("チャンネルをアーカイブしました: {}", channel);
+        Ok(())
+    }
+
+    /// チャンネルのアーカイブを解除します
+    pub async fn unarchive_channel(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post("conversations.unarchive", &json!({ "channel": channel }))
+            .await?;
+        info!("チャンネルのアーカイブを解除しました: {}", channel);
+        Ok(())
+    }
+
+    /// チャンネル名を変更します
+    pub async fn rename_channel(
+        &self,
+        channel: impl Into<ChannelId>,
+        name: &str,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let channel = channel.into();
+        let res: ChannelResponse = self
+            .api_post(
+                "conversations.rename",
+                &json!({ "channel": channel, "name": name }),
+            )
+            .await?;
+        info!("チャンネル名を変更しました: {} -> {}", channel, name);
+        Ok(res.channel)
+    }
+
+    /// チャンネルのトピックを設定します
+    pub async fn set_channel_topic(
+        &self,
+        channel: impl Into<ChannelId>,
+        topic: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post(
+                "conversations.setTopic",
+                &json!({ "channel": channel, "topic": topic }),
+            )
+            .await?;
+        info!("チャンネルのトピックを設定しました: {}", channel);
+        Ok(())
+    }
+
+    /// チャンネルの説明を設定します
+    pub async fn set_channel_purpose(
+        &self,
+        channel: impl Into<ChannelId>,
+        purpose: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post(
+                "conversations.setPurpose",
+                &json!({ "channel": channel, "purpose": purpose }),
+            )
+            .await?;
+        info!("チャンネルの説明を設定しました: {}", channel);
+        Ok(())
+    }
+
+    /// チャンネルの情報を取得します（メンバー数を含みます）
+    pub async fn channel_info(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let channel = channel.into();
+        let params = vec![
+            ("channel", Some(channel.to_string())),
+            ("include_num_members", Some("true".to_string())),
+        ];
+        let res: ChannelResponse = self.api_get("conversations.info", &params).await?;
+        Ok(res.channel)
+    }
+
+    /// チャンネルのメンバーのユーザーIDを取得します
+    ///
+    /// ページングは自動的に行われ、ユーザーIDを1件ずつ返します。
+    pub fn channel_members(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> impl Stream<Item = Result<UserId, Box<dyn Error>>> + '_ {
+        let channel = channel.into();
+        paginate(move |cursor| {
+            let channel = channel.clone();
+            async move {
+                let params = vec![
+                    ("channel", Some(channel.into_inner())),
+                    ("limit", Some(DEFAULT_PAGE_SIZE.to_string())),
+                    ("cursor", cursor),
+                ];
+                let res: MembersResponse = self.api_get("conversations.members", &params).await?;
+                let next_cursor = ResponseMetadata::next_cursor(res.response_metadata);
+                Ok((res.members, next_cursor))
+            }
+        })
+    }
+
+    /// チャンネルの一覧を取得します
+    ///
+    /// ページングは自動的に行われ、チャンネルを1件ずつ返します。
+    pub fn list_channels<'a>(
+        &'a self,
+        options: &ListChannelsOptions,
+    ) -> impl Stream<Item = Result<Channel, Box<dyn Error>>> + 'a {
+        let params = options.params();
+        paginate(move |cursor| {
+            let mut params = params.clone();
+            params.push(("cursor", cursor));
+            async move {
+                let res: ListResponse = self.api_get("conversations.list", &params).await?;
+                let next_cursor = ResponseMetadata::next_cursor(res.response_metadata);
+                Ok((res.channels, next_cursor))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_channel() {
+        let res: ChannelResponse = serde_json::from_value(json!({
+            "ok": true,
+            "channel": {
+                "id": "C0123456789",
+                "name": "incident-1234",
+                "is_channel": true,
+                "is_private": false,
+                "is_archived": false,
+                "is_member": true,
+                "created": 1700000000,
+                "creator": "U1",
+                "topic": {"value": "決済APIの障害対応", "creator": "U1", "last_set": 1700000100},
+                "purpose": {"value": "", "creator": "", "last_set": 0},
+                "num_members": 3
+            }
+        }))
+        .unwrap();
+
+        let channel = res.channel;
+        assert_eq!(channel.name.as_deref(), Some("incident-1234"));
+        assert!(channel.is_channel && channel.is_member && !channel.is_im);
+        assert_eq!(channel.creator.unwrap(), "U1");
+        assert_eq!(channel.topic.unwrap().value, "決済APIの障害対応");
+        assert_eq!(channel.purpose.unwrap().creator, None);
+        assert_eq!(channel.num_members, Some(3));
+    }
+
+    #[test]
+    fn test_list_options_types_filter() {
+        let options = ListChannelsOptions {
+            types: vec![
+                ConversationType::PublicChannel,
+                ConversationType::PrivateChannel,
+            ],
+            exclude_archived: true,
+            ..Default::default()
+        };
+        let params = options.params();
+        assert!(params.contains(&("types", Some("public_channel,private_channel".to_string()))));
+        assert!(params.contains(&("exclude_archived", Some("true".to_string()))));
+        assert!(ListChannelsOptions::default()
+            .params()
+            .contains(&("types", None)));
+    }
+}
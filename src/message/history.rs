@@ -0,0 +1,199 @@
+//! チャンネルやスレッドのメッセージ履歴の取得
+
+use super::{
+    pagination::{paginate, ResponseMetadata},
+    MessageClient, Reaction,
+};
+use crate::types::{BotId, ChannelId, Ts, UserId};
+use futures::{Stream, TryStreamExt};
+use serde::Deserialize;
+use std::error::Error;
+
+/// 1回のリクエストで取得する件数の既定値
+const DEFAULT_PAGE_SIZE: u32 = 200;
+
+/// 履歴から取得したメッセージ
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HistoryMessage {
+    /// メッセージのタイムスタンプ
+    pub ts: Ts,
+    /// スレッドの親メッセージのタイムスタンプ（スレッド外のメッセージでは`None`）
+    pub thread_ts: Option<Ts>,
+    /// 投稿したユーザーのID
+    pub user: Option<UserId>,
+    /// ボットが投稿した場合のボットID
+    pub bot_id: Option<BotId>,
+    /// メッセージのテキスト
+    #[serde(default)]
+    pub text: String,
+    /// メッセージのサブタイプ（`bot_message`、`channel_join`など）
+    pub subtype: Option<String>,
+    /// スレッドの返信数（親メッセージのみ）
+    pub reply_count: Option<u32>,
+    /// 付いているリアクション
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+}
+
+impl HistoryMessage {
+    /// スレッドの親メッセージか
+    pub fn is_thread_parent(&self) -> bool {
+        self.thread_ts.as_ref() == Some(&self.ts)
+    }
+}
+
+/// [`MessageClient::history`]・[`MessageClient::replies`]の取得範囲
+#[derive(Debug, Clone, Default)]
+pub struct HistoryOptions {
+    /// このタイムスタンプより後のメッセージのみ取得します
+    pub oldest: Option<Ts>,
+    /// このタイムスタンプより前のメッセージのみ取得します
+    pub latest: Option<Ts>,
+    /// `oldest`・`latest`と一致するメッセージも含めるか
+    pub inclusive: bool,
+    /// 1回のリクエストで取得する件数（既定値は200）
+    pub page_size: Option<u32>,
+}
+
+impl HistoryOptions {
+    fn params(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("oldest", self.oldest.as_ref().map(Ts::to_string)),
+            ("latest", self.latest.as_ref().map(Ts::to_string)),
+            ("inclusive", self.inclusive.then(|| "true".to_string())),
+            (
+                "limit",
+                Some(self.page_size.unwrap_or(DEFAULT_PAGE_SIZE).to_string()),
+            ),
+        ]
+    }
+}
+
+#[derive(Deserialize)]
+struct HistoryResponse {
+    #[serde(default)]
+    messages: Vec<HistoryMessage>,
+    response_metadata: Option<ResponseMetadata>,
+}
+
+impl MessageClient {
+    /// チャンネルのメッセージ履歴を新しい順に取得します
+    ///
+    /// ページングは自動的に行われ、メッセージを1件ずつ返します。
+    /// スレッドの返信は含まれません（[`replies`](Self::replies)で取得します）。
+    pub fn history<'a>(
+        &'a self,
+        channel: impl Into<ChannelId>,
+        options: &HistoryOptions,
+    ) -> impl Stream<Item = Result<HistoryMessage, Box<dyn Error>>> + 'a {
+        let mut params = options.params();
+        params.push(("channel", Some(channel.into().into_inner())));
+        self.paginate_messages("conversations.history", params)
+    }
+
+    /// スレッドのメッセージを古い順に取得します
+    ///
+    /// 最初の要素は親メッセージです。
+    ///
+    /// # 引数
+    /// * `ts` - 親メッセージのタイムスタンプ
+    pub fn replies<'a>(
+        &'a self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+        options: &HistoryOptions,
+    ) -> impl Stream<Item = Result<HistoryMessage, Box<dyn Error>>> + 'a {
+        let mut params = options.params();
+        params.push(("channel", Some(channel.into().into_inner())));
+        params.push(("ts", Some(ts.to_string())));
+        self.paginate_messages("conversations.replies", params)
+    }
+
+    /// スレッド全体を古い順に取得します
+    ///
+    /// メンションを受け取った場合は[`Event::thread_root`](crate::Event::thread_root)を
+    /// `thread_ts`に渡すと、メンションが属するスレッドを取得できます。
+    pub async fn load_thread(
+        &self,
+        channel: impl Into<ChannelId>,
+        thread_ts: &Ts,
+    ) -> Result<Vec<HistoryMessage>, Box<dyn Error>> {
+        self.replies(channel, thread_ts, &HistoryOptions::default())
+            .try_collect()
+            .await
+    }
+
+    fn paginate_messages<'a>(
+        &'a self,
+        method: &'a str,
+        params: Vec<(&'static str, Option<String>)>,
+    ) -> impl Stream<Item = Result<HistoryMessage, Box<dyn Error>>> + 'a {
+        paginate(move |cursor| {
+            let mut params = params.clone();
+            params.push(("cursor", cursor));
+            async move {
+                let res: HistoryResponse = self.api_get(method, &params).await?;
+                let next_cursor = ResponseMetadata::next_cursor(res.response_metadata);
+                Ok((res.messages, next_cursor))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_replies_response() {
+        let res: HistoryResponse = serde_json::from_value(serde_json::json!({
+            "ok": true,
+            "messages": [
+                {
+                    "type": "message",
+                    "user": "U1",
+                    "text": "<@U0BOT> ビルドが落ちています",
+                    "ts": "1700000000.000100",
+                    "thread_ts": "1700000000.000100",
+                    "reply_count": 1
+                },
+                {
+                    "type": "message",
+                    "subtype": "bot_message",
+                    "bot_id": "B1",
+                    "text": "確認します",
+                    "ts": "1700000000.000200",
+                    "thread_ts": "1700000000.000100",
+                    "reactions": [{"name": "eyes", "count": 1, "users": ["U1"]}]
+                }
+            ],
+            "has_more": true,
+            "response_metadata": {"next_cursor": "bmV4dA=="}
+        }))
+        .unwrap();
+
+        assert!(res.messages[0].is_thread_parent());
+        assert_eq!(res.messages[0].reply_count, Some(1));
+        assert!(!res.messages[1].is_thread_parent());
+        assert_eq!(res.messages[1].subtype.as_deref(), Some("bot_message"));
+        assert_eq!(res.messages[1].reactions[0].name, "eyes");
+        assert_eq!(
+            ResponseMetadata::next_cursor(res.response_metadata).as_deref(),
+            Some("bmV4dA==")
+        );
+    }
+
+    #[test]
+    fn test_history_options_params() {
+        let options = HistoryOptions {
+            oldest: Some("1700000000.000000".parse().unwrap()),
+            inclusive: true,
+            ..Default::default()
+        };
+        let params = options.params();
+        assert!(params.contains(&("oldest", Some("1700000000.000000".to_string()))));
+        assert!(params.contains(&("latest", None)));
+        assert!(params.contains(&("inclusive", Some("true".to_string()))));
+        assert!(params.contains(&("limit", Some("200".to_string()))));
+    }
+}
@@ -0,0 +1,274 @@
+//! Incoming WebhookのURLへのメッセージ送信
+
+use crate::{
+    blocks::{fallback_text, Block},
+    error::SlackError,
+    retry::RetryConfig,
+    types::Ts,
+};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{header, Request, StatusCode};
+use hyper_rustls::HttpsConnector;
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::TokioExecutor,
+};
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Incoming Webhookで送信するメッセージ
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WebhookMessage {
+    /// テキスト（ブロックがある場合は通知やスクリーンリーダーで使用されます）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Block>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// スレッドに返信する場合は親メッセージのタイムスタンプ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_ts: Option<Ts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unfurl_links: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unfurl_media: Option<bool>,
+}
+
+/// メッセージの添付（左端に色付きの線が表示されます）
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Attachment {
+    /// 線の色（`good`、`warning`、`danger`または`#36a64f`のような16進数）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// 通知などで表示されるテキスト
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pretext: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Block>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+}
+
+/// Incoming WebhookのURLにメッセージを送信するクライアント
+///
+/// ボットトークンは不要です。レート制限（HTTP 429）に加えて、
+/// [`MessageClient`](crate::MessageClient)とは異なりサーバーエラーや通信エラーの場合も
+/// [`RetryConfig`]に従って再試行します。
+#[derive(Clone)]
+pub struct IncomingWebhookClient {
+    url: String,
+    http: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+    retry: RetryConfig,
+}
+
+impl IncomingWebhookClient {
+    /// クライアントを作成します
+    ///
+    /// # 引数
+    /// * `url` - Incoming WebhookのURL（`https://hooks.slack.com/services/...`）
+    ///
+    /// # エラー
+    /// システムのルート証明書を読み込めない場合は[`SlackError::Transport`]を返します。
+    pub fn new(url: impl Into<String>) -> Result<Self, SlackError> {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .map_err(|e| {
+                SlackError::Transport(format!("ルート証明書の読み込みに失敗しました: {}", e))
+            })?
+            .https_or_http()
+            .enable_http1()
+            .build();
+        Ok(Self {
+            url: url.into(),
+            http: Client::builder(TokioExecutor::new()).build(connector),
+            retry: RetryConfig::default(),
+        })
+    }
+
+    /// 再試行の設定を変更します
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// テキストメッセージを送信します
+    pub async fn send_text(&self, text: &str) -> Result<(), SlackError> {
+        self.send(&WebhookMessage {
+            text: Some(text.to_string()),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// ブロックを送信します
+    ///
+    /// 通知やスクリーンリーダー向けの`text`は、ブロックの内容から自動的に生成されます。
+    pub async fn send_blocks(&self, blocks: Vec<Block>) -> Result<(), SlackError> {
+        let text = fallback_text(&blocks);
+        self.send(&WebhookMessage {
+            text: (!text.is_empty()).then_some(text),
+            blocks,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// メッセージを送信します
+    pub async fn send(&self, message: &WebhookMessage) -> Result<(), SlackError> {
+        let body = Bytes::from(serde_json::to_vec(message)?);
+        let mut attempt = 0;
+        loop {
+            let error = match self.post(body.clone()).await {
+                Ok(()) => {
+                    info!("Incoming Webhookでメッセージを送信しました");
+                    return Ok(());
+                }
+                Err(e) if e.is_retryable() => e,
+                Err(e) => {
+                    warn!("Incoming Webhookでの送信に失敗しました: {}", e);
+                    return Err(e);
+                }
+            };
+            let retry_after = match &error {
+                SlackError::RateLimited { retry_after } => *retry_after,
+                _ => None,
+            };
+            let Some(delay) = self.retry.delay(attempt, retry_after) else {
+                warn!("Incoming Webhookでの送信に失敗しました: {}", error);
+                return Err(error);
+            };
+            warn!(
+                "Incoming Webhookでの送信を{}秒後に再試行します: {}",
+                delay.as_secs_f32(),
+                error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn post(&self, body: Bytes) -> Result<(), SlackError> {
+        let request = Request::post(&self.url)
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Full::new(body))
+            .map_err(|e| SlackError::Transport(e.to_string()))?;
+        let response = self
+            .http
+            .request(request)
+            .await
+            .map_err(|e| SlackError::Transport(e.to_string()))?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs);
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| SlackError::Transport(e.to_string()))?
+            .to_bytes();
+        map_response(status, &String::from_utf8_lossy(&body), retry_after)
+    }
+}
+
+/// Incoming Webhookのレスポンスをエラーに変換します
+///
+/// Incoming Webhookはエラーを`invalid_payload`のようなプレーンテキストで返します。
+fn map_response(
+    status: StatusCode,
+    body: &str,
+    retry_after: Option<Duration>,
+) -> Result<(), SlackError> {
+    let body = body.trim();
+    if status.is_success() {
+        return Ok(());
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(SlackError::RateLimited { retry_after });
+    }
+    let is_code = !body.is_empty()
+        && body
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_code {
+        Err(SlackError::Api {
+            code: body.to_string(),
+        })
+    } else {
+        Err(SlackError::Http {
+            status: status.as_u16(),
+            body: body.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextObject;
+
+    #[test]
+    fn test_map_error_responses() {
+        assert!(map_response(StatusCode::OK, "ok", None).is_ok());
+        for (status, code) in [
+            (StatusCode::BAD_REQUEST, "invalid_payload"),
+            (StatusCode::GONE, "channel_is_archived"),
+            (StatusCode::NOT_FOUND, "no_service"),
+        ] {
+            let error = map_response(status, code, None).unwrap_err();
+            assert_eq!(error.code(), Some(code));
+            assert!(!error.is_retryable());
+        }
+        assert!(matches!(
+            map_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                "",
+                Some(Duration::from_secs(3))
+            ),
+            Err(SlackError::RateLimited {
+                retry_after: Some(_)
+            })
+        ));
+        let error =
+            map_response(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>", None).unwrap_err();
+        assert!(matches!(error, SlackError::Http { status: 502, .. }));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_message_serialization() {
+        let message = WebhookMessage {
+            text: Some("ジョブが失敗しました".to_string()),
+            attachments: vec![Attachment {
+                color: Some("danger".to_string()),
+                blocks: vec![Block::section(TextObject::mrkdwn("*nightly-build* #42"))],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "text": "ジョブが失敗しました",
+                "attachments": [{
+                    "color": "danger",
+                    "blocks": [{"type": "section", "text": {"type": "mrkdwn", "text": "*nightly-build* #42"}}]
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! メッセージに付与する構造化されたメタデータ
+
+use super::{MessageClient, PostedMessage};
+use crate::{
+    blocks::{fallback_text, Block},
+    types::ChannelId,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use tracing::info;
+
+/// メッセージのメタデータ
+///
+/// `event_type`でメタデータの種類を表し、`event_payload`に任意のJSONを保持します。
+/// 受信側は`message_metadata_posted`などのイベントや、メッセージの`metadata`で受け取れます。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageMetadata {
+    /// メタデータの種類（`deployment_started`など）
+    pub event_type: String,
+    /// メタデータの内容
+    #[serde(default)]
+    pub event_payload: Value,
+}
+
+impl MessageMetadata {
+    /// 型付きの内容からメタデータを作成します
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use slack_rs::MessageMetadata;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Deployment {
+    ///     id: String,
+    ///     version: String,
+    /// }
+    ///
+    /// let deployment = Deployment { id: "d-123".into(), version: "1.2.0".into() };
+    /// let metadata = MessageMetadata::new("deployment_started", &deployment).unwrap();
+    /// let payload: Deployment = metadata.payload().unwrap();
+    /// assert_eq!(payload.id, "d-123");
+    /// ```
+    pub fn new<T: Serialize>(
+        event_type: impl Into<String>,
+        payload: &T,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            event_type: event_type.into(),
+            event_payload: serde_json::to_value(payload)?,
+        })
+    }
+
+    /// 内容を型付きの値に変換します
+    pub fn payload<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.event_payload)
+    }
+}
+
+impl MessageClient {
+    /// メタデータを付けてテキストメッセージを送信します
+    pub async fn send_text_with_metadata(
+        &self,
+        channel: impl Into<ChannelId>,
+        text: &str,
+        metadata: &MessageMetadata,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let channel = channel.into();
+        self.post_with_metadata(json!({ "channel": channel, "text": text }), metadata)
+            .await
+    }
+
+    /// メタデータを付けてブロックを送信します
+    ///
+    /// 通知やスクリーンリーダー向けの`text`は、ブロックの内容から自動的に生成されます。
+    pub async fn send_blocks_with_metadata(
+        &self,
+        channel: impl Into<ChannelId>,
+        blocks: Vec<Block>,
+        metadata: &MessageMetadata,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let channel = channel.into();
+        let mut body = json!({ "channel": channel, "blocks": blocks });
+        let text = fallback_text(&blocks);
+        if !text.is_empty() {
+            body["text"] = json!(text);
+        }
+        self.post_with_metadata(body, metadata).await
+    }
+
+    /// `chat.postMessage`にメタデータを付けて送信します
+    ///
+    /// slack-morphismのメタデータは文字列の値しか扱えないため、JSONを直接送信します。
+    async fn post_with_metadata(
+        &self,
+        mut body: Value,
+        metadata: &MessageMetadata,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        body["metadata"] = json!(metadata);
+        let posted = self.post_json(&body).await?;
+        info!(
+            "メタデータ付きのメッセージを送信しました: {} (ts: {}, event_type: {})",
+            posted.channel, posted.ts, metadata.event_type
+        );
+        Ok(posted)
+    }
+}
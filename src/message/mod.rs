@@ -0,0 +1,522 @@
+use crate::{
+    blocks::{fallback_text, Block},
+    mrkdwn,
+    retry::RetryConfig,
+    types::{ChannelId, InvalidTokenKind, Token, TokenKind, Ts},
+};
+use hyper_rustls::HttpsConnector;
+use hyper_util::client::legacy::connect::HttpConnector;
+use serde::Deserialize;
+use serde_json::json;
+use slack_morphism::{hyper_tokio::SlackClientHyperConnector, prelude::*};
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    sync::{Arc, Mutex},
+};
+use tracing::{info, warn};
+
+#[cfg(feature = "message")]
+mod auth;
+#[cfg(feature = "message")]
+mod conversations;
+#[cfg(feature = "message")]
+mod history;
+#[cfg(feature = "message")]
+mod incoming_webhook;
+#[cfg(feature = "message")]
+mod metadata;
+#[cfg(feature = "message")]
+mod pagination;
+#[cfg(feature = "message")]
+mod pins;
+#[cfg(feature = "message")]
+mod reactions;
+#[cfg(feature = "message")]
+mod stream;
+#[cfg(feature = "message")]
+mod unfurl;
+#[cfg(feature = "message")]
+mod users;
+#[cfg(feature = "message")]
+mod views;
+
+#[cfg(feature = "message")]
+pub use auth::AuthInfo;
+#[cfg(feature = "message")]
+pub use conversations::{Channel, ChannelText, ConversationType, ListChannelsOptions};
+#[cfg(feature = "message")]
+pub use history::{HistoryMessage, HistoryOptions};
+#[cfg(feature = "message")]
+pub use incoming_webhook::{Attachment, IncomingWebhookClient, WebhookMessage};
+#[cfg(feature = "message")]
+pub use metadata::MessageMetadata;
+#[cfg(feature = "message")]
+pub use pins::{Bookmark, BookmarkUpdate, PinnedItem};
+#[cfg(feature = "message")]
+pub use reactions::{ReactedItem, Reaction, StatusReactions};
+#[cfg(feature = "message")]
+pub use stream::{MessageStream, MessageStreamOptions};
+#[cfg(feature = "message")]
+pub use users::{Presence, User, UserCache, UserProfile};
+#[cfg(feature = "message")]
+pub use views::ViewInfo;
+
+/// `chat.postMessage`で送信できるテキストの最大文字数（超過分は切り捨てられます）
+pub const MAX_MESSAGE_LENGTH: usize = 40_000;
+
+/// 読みやすく表示されるテキストの推奨最大文字数
+pub const RECOMMENDED_MESSAGE_LENGTH: usize = 4_000;
+
+/// [`MessageClient::send_long_text`]の分割設定
+#[derive(Debug, Clone)]
+pub struct LongMessageOptions {
+    /// 1メッセージあたりの最大文字数（0の場合は1として扱います）
+    pub max_length: usize,
+    /// 2通目以降を1通目のスレッドに投稿するか
+    pub in_thread: bool,
+}
+
+impl Default for LongMessageOptions {
+    fn default() -> Self {
+        Self {
+            max_length: RECOMMENDED_MESSAGE_LENGTH,
+            in_thread: false,
+        }
+    }
+}
+
+/// 投稿したメッセージ
+///
+/// ピン留めやパーマリンクの取得など、メッセージを指定するメソッドにそのまま渡せます。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostedMessage {
+    /// チャンネルID
+    pub channel: ChannelId,
+    /// メッセージのタイムスタンプ
+    pub ts: Ts,
+}
+
+/// `chat.postMessage`のレスポンス
+#[derive(Deserialize)]
+struct PostMessageResponse {
+    channel: ChannelId,
+    ts: Ts,
+}
+
+/// 再試行の設定に従ってレート制御するコネクタを作成します
+///
+/// `api_url`を指定した場合は、テスト用のサーバーなどに`http://`でも接続できます。
+#[cfg(feature = "message")]
+fn connector(
+    retry: &RetryConfig,
+    api_url: Option<&str>,
+) -> SlackClientHyperConnector<HttpsConnector<HttpConnector>> {
+    let connector = match api_url {
+        None => SlackClientHyperConnector::new().expect("HTTPクライアントの作成に失敗しました"),
+        Some(api_url) => SlackClientHyperConnector::from(
+            hyper_rustls::HttpsConnectorBuilder::new()
+                .with_native_roots()
+                .expect("HTTPクライアントの作成に失敗しました")
+                .https_or_http()
+                .enable_http1()
+                .enable_http2()
+                .build(),
+        )
+        .with_slack_api_url(api_url.trim_end_matches('/')),
+    };
+    connector.with_rate_control(retry.rate_control())
+}
+
+#[cfg(feature = "message")]
+#[derive(Clone)]
+pub struct MessageClient {
+    client: Arc<SlackClient<SlackClientHyperConnector<HttpsConnector<HttpConnector>>>>,
+    token: Token,
+    retry: RetryConfig,
+    /// 呼び出したAPIメソッド（複製したクライアントと共有）
+    api_methods: Arc<Mutex<BTreeSet<String>>>,
+}
+
+#[cfg(feature = "message")]
+impl MessageClient {
+    /// クライアントを作成します
+    ///
+    /// アプリレベルトークン（`xapp-`）はWeb APIの呼び出しに使用できないため、エラーを返します。
+    pub fn new(token: Token) -> Result<Self, InvalidTokenKind> {
+        Self::with_retry(token, RetryConfig::default())
+    }
+
+    /// 再試行の設定を指定してクライアントを作成します
+    ///
+    /// レート制限（HTTP 429）の場合は`Retry-After`に従って待機し、`retry.max_retries`回まで再試行します。
+    /// サーバーエラーや通信エラーは再試行しません（[`RetryConfig`]を参照）。
+    pub fn with_retry(token: Token, retry: RetryConfig) -> Result<Self, InvalidTokenKind> {
+        if token.kind() == TokenKind::App {
+            return Err(InvalidTokenKind {
+                expected: None,
+                actual: token.kind(),
+            });
+        }
+        Ok(Self {
+            client: Arc::new(SlackClient::new(connector(&retry, None))),
+            token,
+            retry,
+            api_methods: Arc::default(),
+        })
+    }
+
+    /// Web APIのベースURLを変更します（既定は`https://slack.com/api`）
+    ///
+    /// プロキシやテスト用のサーバーに接続する場合に使用します。既定と異なり`http://`も使用できます。
+    pub fn with_api_url(self, api_url: impl Into<String>) -> Self {
+        let connector = connector(&self.retry, Some(&api_url.into()));
+        Self {
+            client: Arc::new(SlackClient::new(connector)),
+            ..self
+        }
+    }
+
+    /// このクライアント（と複製したクライアント）がこれまでに呼び出したAPIメソッド
+    ///
+    /// [`AppManifest::with_client`](crate::AppManifest::with_client)で、
+    /// 必要なボットスコープをマニフェストに追加するのに使います。
+    pub fn api_methods(&self) -> BTreeSet<String> {
+        self.api_methods.lock().unwrap().clone()
+    }
+
+    /// APIメソッドの呼び出しを記録し、セッションを開くためのトークンを返します
+    fn api_token(&self, method: &str) -> SlackApiToken {
+        self.api_methods.lock().unwrap().insert(method.to_string());
+        self.token.clone().into()
+    }
+
+    pub async fn send_text(
+        &self,
+        channel: impl Into<ChannelId>,
+        text: &str,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let content = SlackMessageContent::new().with_text(text.into());
+        self.send_message(channel, content).await
+    }
+
+    /// ブロックを送信します
+    ///
+    /// 通知やスクリーンリーダー向けの`text`は、ブロックの内容から自動的に生成されます。
+    pub async fn send_blocks(
+        &self,
+        channel: impl Into<ChannelId>,
+        blocks: Vec<Block>,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let text = fallback_text(&blocks);
+        self.send_blocks_with_text(channel, &text, blocks).await
+    }
+
+    /// 代替テキストを指定してブロックを送信します
+    ///
+    /// `text`は通知やスクリーンリーダーで表示されます。空文字列の場合は設定されません。
+    /// slack-morphismが表現できないブロック（[`Block::Unknown`]）もそのまま送信できるよう、JSONを直接送信します。
+    pub async fn send_blocks_with_text(
+        &self,
+        channel: impl Into<ChannelId>,
+        text: &str,
+        blocks: Vec<Block>,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let mut body = json!({ "channel": channel.into(), "blocks": blocks });
+        if !text.is_empty() {
+            body["text"] = json!(text);
+        }
+        let posted = self.post_json(&body).await?;
+        info!(
+            "メッセージを送信しました: {} (ts: {})",
+            posted.channel, posted.ts
+        );
+        Ok(posted)
+    }
+
+    /// 長いテキストを分割して送信します
+    ///
+    /// 段落・行・コードブロックの区切りで`options.max_length`文字以内に分割し、順番に投稿します。
+    /// メンションやリンクの途中では分割しません。
+    ///
+    /// # 戻り値
+    /// 投稿したメッセージのタイムスタンプ（投稿順）
+    pub async fn send_long_text(
+        &self,
+        channel: impl Into<ChannelId>,
+        text: &str,
+        options: &LongMessageOptions,
+    ) -> Result<Vec<Ts>, Box<dyn Error>> {
+        let channel = channel.into();
+        let mut timestamps: Vec<Ts> = Vec::new();
+        for part in mrkdwn::split(text, options.max_length) {
+            let thread_ts = if options.in_thread {
+                timestamps.first().cloned()
+            } else {
+                None
+            };
+            let content = SlackMessageContent::new().with_text(part);
+            let posted = self
+                .post_message(&channel, content, thread_ts.as_ref())
+                .await?;
+            timestamps.push(posted.ts);
+        }
+        Ok(timestamps)
+    }
+
+    async fn send_message(
+        &self,
+        channel: impl Into<ChannelId>,
+        content: SlackMessageContent,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        self.post_message(&channel.into(), content, None).await
+    }
+
+    /// メッセージを投稿します
+    async fn post_message(
+        &self,
+        channel: &ChannelId,
+        content: SlackMessageContent,
+        thread_ts: Option<&Ts>,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let mut req = SlackApiChatPostMessageRequest::new(channel.clone().into(), content);
+        if let Some(thread_ts) = thread_ts {
+            req = req.with_thread_ts(thread_ts.clone().into());
+        }
+        let token = self.api_token("chat.postMessage");
+        let session = self.client.open_session(&token);
+        match session.chat_post_message(&req).await {
+            Ok(res) => {
+                info!("メッセージを送信しました: {} (ts: {})", channel, res.ts);
+                Ok(PostedMessage {
+                    channel: res.channel.into(),
+                    ts: res.ts.try_into()?,
+                })
+            }
+            Err(e) => {
+                warn!("メッセージの送信に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// `chat.postMessage`にJSONを直接送信します
+    async fn post_json(&self, body: &serde_json::Value) -> Result<PostedMessage, Box<dyn Error>> {
+        let res: PostMessageResponse = self.api_post("chat.postMessage", body).await?;
+        Ok(PostedMessage {
+            channel: res.channel,
+            ts: res.ts,
+        })
+    }
+
+    /// APIメソッドをGETで呼び出し、レスポンスを`RS`として受け取ります
+    async fn api_get<RS>(
+        &self,
+        method: &str,
+        params: &Vec<(&str, Option<String>)>,
+    ) -> Result<RS, Box<dyn Error>>
+    where
+        RS: for<'de> serde::Deserialize<'de> + Send,
+    {
+        let token = self.api_token(method);
+        let session = self.client.open_session(&token);
+        match session
+            .http_session_api
+            .http_get(method, params, None)
+            .await
+        {
+            Ok(res) => Ok(res),
+            Err(e) => {
+                warn!("{}の呼び出しに失敗しました: {:?}", method, e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// APIメソッドをJSONのPOSTで呼び出し、レスポンスを`RS`として受け取ります
+    async fn api_post<RS>(
+        &self,
+        method: &str,
+        body: &serde_json::Value,
+    ) -> Result<RS, Box<dyn Error>>
+    where
+        RS: for<'de> serde::Deserialize<'de> + Send,
+    {
+        let token = self.api_token(method);
+        let session = self.client.open_session(&token);
+        match session.http_session_api.http_post(method, body, None).await {
+            Ok(res) => Ok(res),
+            Err(e) => {
+                warn!("{}の呼び出しに失敗しました: {:?}", method, e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    pub async fn reply_to_thread(
+        &self,
+        channel: impl Into<ChannelId>,
+        thread_ts: &Ts,
+        text: &str,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let channel = channel.into();
+        let channel_id = channel.clone().into();
+        let content = SlackMessageContent::new().with_text(text.into());
+        let req = SlackApiChatPostMessageRequest::new(channel_id, content)
+            .with_thread_ts(thread_ts.clone().into());
+        let token = self.api_token("chat.postMessage");
+        let session = self.client.open_session(&token);
+        match session.chat_post_message(&req).await {
+            Ok(res) => {
+                info!(
+                    "スレッドに返信しました: {} (thread_ts: {})",
+                    channel, thread_ts
+                );
+                Ok(PostedMessage {
+                    channel: res.channel.into(),
+                    ts: res.ts.try_into()?,
+                })
+            }
+            Err(e) => {
+                warn!("スレッドへの返信に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// スレッドにブロックで返信します
+    ///
+    /// 代替テキストは[`send_blocks`](Self::send_blocks)と同じく、ブロックの内容から自動的に生成されます。
+    pub async fn reply_blocks_to_thread(
+        &self,
+        channel: impl Into<ChannelId>,
+        thread_ts: &Ts,
+        blocks: Vec<Block>,
+    ) -> Result<PostedMessage, Box<dyn Error>> {
+        let text = fallback_text(&blocks);
+        let mut body = json!({
+            "channel": channel.into(),
+            "thread_ts": thread_ts,
+            "blocks": blocks,
+        });
+        if !text.is_empty() {
+            body["text"] = json!(text);
+        }
+        let posted = self.post_json(&body).await?;
+        info!(
+            "スレッドに返信しました: {} (thread_ts: {})",
+            posted.channel, thread_ts
+        );
+        Ok(posted)
+    }
+
+    pub async fn update_message(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+        text: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let channel_id = channel.clone().into();
+        let content = SlackMessageContent::new().with_text(text.into());
+        let req = SlackApiChatUpdateRequest::new(channel_id, content, ts.clone().into());
+        let token = self.api_token("chat.update");
+        let session = self.client.open_session(&token);
+        match session.chat_update(&req).await {
+            Ok(_) => {
+                info!("メッセージを更新しました: {} (ts: {})", channel, ts);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("メッセージの更新に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    pub async fn delete_message(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let channel_id = channel.clone().into();
+        let req = SlackApiChatDeleteRequest::new(channel_id, ts.clone().into());
+        let token = self.api_token("chat.delete");
+        let session = self.client.open_session(&token);
+        match session.chat_delete(&req).await {
+            Ok(_) => {
+                info!("メッセージを削除しました: {} (ts: {})", channel, ts);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("メッセージの削除に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    pub async fn upload_file(
+        &self,
+        channels: Vec<ChannelId>,
+        file: Vec<u8>,
+        filename: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel_ids: Vec<SlackChannelId> = channels.into_iter().map(Into::into).collect();
+        let req = SlackApiFilesUploadRequest::new()
+            .with_channels(channel_ids)
+            .with_filename(filename.into())
+            .with_content(String::from_utf8_lossy(&file).into_owned());
+        let token = self.api_token("files.upload");
+        let session = self.client.open_session(&token);
+        #[allow(deprecated)]
+        match session.files_upload(&req).await {
+            Ok(_) => {
+                info!("ファイルをアップロードしました: {}", filename);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("ファイルのアップロードに失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_app_token() {
+        assert!(MessageClient::new(Token::new("xoxb-test")).is_ok());
+        let error = MessageClient::new(Token::new("xapp-test")).err().unwrap();
+        assert_eq!(error.actual, TokenKind::App);
+    }
+
+    #[test]
+    fn test_records_api_methods() {
+        let client = MessageClient::new(Token::new("xoxb-test")).unwrap();
+        let cloned = client.clone();
+        cloned.api_token("chat.postMessage");
+        client.api_token("reactions.add");
+        client.api_token("chat.postMessage");
+        assert_eq!(
+            client.api_methods().into_iter().collect::<Vec<_>>(),
+            vec!["chat.postMessage", "reactions.add"]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_send_text_message() {
+        let token = std::env::var("SLACK_BOT_TOKEN").expect("SLACK_BOT_TOKEN must be set");
+        let channel: ChannelId = std::env::var("SLACK_CHANNEL_ID")
+            .expect("SLACK_CHANNEL_ID must be set")
+            .parse()
+            .unwrap();
+        let token = Token::new(token);
+        let client = MessageClient::new(token).unwrap();
+        client.send_text(channel, "テストメッセージ").await.unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+//! カーソルによるページングをStreamとして扱う
+
+use futures::{stream, Stream, TryStreamExt};
+use serde::Deserialize;
+use std::{error::Error, future::Future};
+
+/// ページングされたレスポンスのメタデータ
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ResponseMetadata {
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+impl ResponseMetadata {
+    /// 次のページのカーソル（最後のページの場合は`None`）
+    pub fn next_cursor(metadata: Option<Self>) -> Option<String> {
+        metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|cursor| !cursor.is_empty())
+    }
+}
+
+/// `fetch`でページを順に取得し、各要素を返すStreamを作成します。
+///
+/// `fetch`はカーソルを受け取り、そのページの要素と次のページのカーソルを返します。
+/// 次のカーソルが`None`になった時点で終了します。
+pub(crate) fn paginate<'a, T, F, Fut>(
+    fetch: F,
+) -> impl Stream<Item = Result<T, Box<dyn Error>>> + 'a
+where
+    T: 'a,
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>), Box<dyn Error>>> + 'a,
+{
+    stream::try_unfold(
+        (fetch, None::<String>, false),
+        |(mut fetch, cursor, done)| async move {
+            if done {
+                return Ok::<_, Box<dyn Error>>(None);
+            }
+            let (items, next_cursor) = fetch(cursor).await?;
+            let done = next_cursor.is_none();
+            Ok(Some((items, (fetch, next_cursor, done))))
+        },
+    )
+    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+
+    #[tokio::test]
+    async fn test_paginate_follows_cursors() {
+        let pages = paginate(|cursor| async move {
+            Ok(match cursor.as_deref() {
+                None => (vec![1, 2], Some("page2".to_string())),
+                Some("page2") => (vec![3], Some("page3".to_string())),
+                _ => (vec![4], None),
+            })
+        });
+        let items: Vec<i32> = pages.try_collect().await.unwrap();
+        assert_eq!(items, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_empty_cursor_ends_pagination() {
+        let metadata = ResponseMetadata {
+            next_cursor: Some(String::new()),
+        };
+        assert_eq!(ResponseMetadata::next_cursor(Some(metadata)), None);
+    }
+}
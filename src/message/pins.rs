@@ -0,0 +1,326 @@
+//! ピン留め・ブックマーク・パーマリンク
+
+use super::MessageClient;
+use crate::types::{ChannelId, FileId, Ts, UserId};
+use serde::{de::IgnoredAny, Deserialize};
+use serde_json::json;
+use std::error::Error;
+use tracing::info;
+
+/// ピン留めされたアイテム
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinnedItem {
+    /// メッセージ
+    Message {
+        channel: ChannelId,
+        ts: Ts,
+        text: Option<String>,
+        user: Option<UserId>,
+        /// ピン留めしたユーザーのID
+        pinned_by: Option<UserId>,
+    },
+    /// ファイル
+    File { id: FileId, name: Option<String> },
+    /// 未対応の種類のアイテム
+    Unknown(serde_json::Value),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RawPinnedItem {
+    Message {
+        channel: ChannelId,
+        message: RawMessage,
+        created_by: Option<UserId>,
+    },
+    File {
+        file: RawFile,
+    },
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    ts: Ts,
+    text: Option<String>,
+    user: Option<UserId>,
+}
+
+#[derive(Deserialize)]
+struct RawFile {
+    id: FileId,
+    name: Option<String>,
+}
+
+impl From<serde_json::Value> for PinnedItem {
+    fn from(value: serde_json::Value) -> Self {
+        match serde_json::from_value::<RawPinnedItem>(value.clone()) {
+            Ok(RawPinnedItem::Message {
+                channel,
+                message,
+                created_by,
+            }) => Self::Message {
+                channel,
+                ts: message.ts,
+                text: message.text,
+                user: message.user,
+                pinned_by: created_by,
+            },
+            Ok(RawPinnedItem::File { file }) => Self::File {
+                id: file.id,
+                name: file.name,
+            },
+            Err(_) => Self::Unknown(value),
+        }
+    }
+}
+
+/// チャンネルのブックマーク
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Bookmark {
+    /// ブックマークID
+    pub id: String,
+    pub channel_id: ChannelId,
+    pub title: String,
+    pub link: Option<String>,
+    /// 絵文字（`:books:`など）
+    pub emoji: Option<String>,
+    pub icon_url: Option<String>,
+    /// 種類（`link`など）
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// 作成日時（UNIX時間）
+    pub date_created: Option<i64>,
+    /// 更新日時（UNIX時間）
+    pub date_updated: Option<i64>,
+}
+
+/// [`MessageClient::edit_bookmark`]で変更する項目（`None`の項目は変更されません）
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkUpdate {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub emoji: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PinsListResponse {
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct BookmarkResponse {
+    bookmark: Bookmark,
+}
+
+#[derive(Deserialize)]
+struct BookmarksListResponse {
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Deserialize)]
+struct PermalinkResponse {
+    permalink: String,
+}
+
+impl MessageClient {
+    /// メッセージをピン留めします
+    pub async fn pin_message(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post("pins.add", &json!({ "channel": channel, "timestamp": ts }))
+            .await?;
+        info!("メッセージをピン留めしました: {} (ts: {})", channel, ts);
+        Ok(())
+    }
+
+    /// メッセージのピン留めを外します
+    pub async fn unpin_message(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post(
+                "pins.remove",
+                &json!({ "channel": channel, "timestamp": ts }),
+            )
+            .await?;
+        info!("メッセージのピン留めを外しました: {} (ts: {})", channel, ts);
+        Ok(())
+    }
+
+    /// チャンネルのピン留めされたアイテムを取得します
+    pub async fn list_pins(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> Result<Vec<PinnedItem>, Box<dyn Error>> {
+        let channel = channel.into();
+        let params = vec![("channel", Some(channel.to_string()))];
+        let res: PinsListResponse = self.api_get("pins.list", &params).await?;
+        Ok(res.items.into_iter().map(PinnedItem::from).collect())
+    }
+
+    /// チャンネルにリンクのブックマークを追加します
+    ///
+    /// # 引数
+    /// * `emoji` - タイトルの前に表示する絵文字（`:books:`など）
+    pub async fn add_bookmark(
+        &self,
+        channel: impl Into<ChannelId>,
+        title: &str,
+        link: &str,
+        emoji: Option<&str>,
+    ) -> Result<Bookmark, Box<dyn Error>> {
+        let channel = channel.into();
+        let mut body = json!({
+            "channel_id": channel,
+            "title": title,
+            "type": "link",
+            "link": link,
+        });
+        if let Some(emoji) = emoji {
+            body["emoji"] = json!(emoji);
+        }
+        let res: BookmarkResponse = self.api_post("bookmarks.add", &body).await?;
+        info!("ブックマークを追加しました: {} ({})", channel, title);
+        Ok(res.bookmark)
+    }
+
+    /// ブックマークを変更します
+    pub async fn edit_bookmark(
+        &self,
+        channel: impl Into<ChannelId>,
+        bookmark_id: &str,
+        update: &BookmarkUpdate,
+    ) -> Result<Bookmark, Box<dyn Error>> {
+        let channel = channel.into();
+        let mut body = json!({ "channel_id": channel, "bookmark_id": bookmark_id });
+        for (key, value) in [
+            ("title", &update.title),
+            ("link", &update.link),
+            ("emoji", &update.emoji),
+        ] {
+            if let Some(value) = value {
+                body[key] = json!(value);
+            }
+        }
+        let res: BookmarkResponse = self.api_post("bookmarks.edit", &body).await?;
+        info!("ブックマークを変更しました: {} ({})", channel, bookmark_id);
+        Ok(res.bookmark)
+    }
+
+    /// ブックマークを削除します
+    pub async fn remove_bookmark(
+        &self,
+        channel: impl Into<ChannelId>,
+        bookmark_id: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let _: IgnoredAny = self
+            .api_post(
+                "bookmarks.remove",
+                &json!({ "channel_id": channel, "bookmark_id": bookmark_id }),
+            )
+            .await?;
+        info!("ブックマークを削除しました: {} ({})", channel, bookmark_id);
+        Ok(())
+    }
+
+    /// チャンネルのブックマークを取得します
+    pub async fn list_bookmarks(
+        &self,
+        channel: impl Into<ChannelId>,
+    ) -> Result<Vec<Bookmark>, Box<dyn Error>> {
+        let channel = channel.into();
+        let params = vec![("channel_id", Some(channel.to_string()))];
+        let res: BookmarksListResponse = self.api_get("bookmarks.list", &params).await?;
+        Ok(res.bookmarks)
+    }
+
+    /// メッセージのパーマリンクを取得します
+    pub async fn get_permalink(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+    ) -> Result<String, Box<dyn Error>> {
+        let channel = channel.into();
+        let params = vec![
+            ("channel", Some(channel.to_string())),
+            ("message_ts", Some(ts.to_string())),
+        ];
+        let res: PermalinkResponse = self.api_get("chat.getPermalink", &params).await?;
+        Ok(res.permalink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pins_list() {
+        let res: PinsListResponse = serde_json::from_value(json!({
+            "ok": true,
+            "items": [
+                {
+                    "type": "message",
+                    "created": 1700000100,
+                    "created_by": "U2",
+                    "channel": "C1",
+                    "message": {"type": "message", "ts": "1700000000.000100", "text": "v1.2.0をリリースしました", "user": "U1"}
+                },
+                {"type": "file", "file": {"id": "F1", "name": "runbook.pdf"}}
+            ]
+        }))
+        .unwrap();
+
+        let items: Vec<PinnedItem> = res.items.into_iter().map(PinnedItem::from).collect();
+        assert_eq!(
+            items[0],
+            PinnedItem::Message {
+                channel: ChannelId::new("C1").unwrap(),
+                ts: Ts::new("1700000000.000100").unwrap(),
+                text: Some("v1.2.0をリリースしました".to_string()),
+                user: Some(UserId::new("U1").unwrap()),
+                pinned_by: Some(UserId::new("U2").unwrap()),
+            }
+        );
+        assert_eq!(
+            items[1],
+            PinnedItem::File {
+                id: FileId::new("F1").unwrap(),
+                name: Some("runbook.pdf".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_bookmark() {
+        let res: BookmarkResponse = serde_json::from_value(json!({
+            "ok": true,
+            "bookmark": {
+                "id": "Bk1",
+                "channel_id": "C1",
+                "title": "Runbook",
+                "link": "https://example.com/runbook",
+                "emoji": ":books:",
+                "icon_url": null,
+                "type": "link",
+                "date_created": 1700000000,
+                "date_updated": 0,
+                "rank": "U"
+            }
+        }))
+        .unwrap();
+        assert_eq!(res.bookmark.title, "Runbook");
+        assert_eq!(res.bookmark.kind, "link");
+        assert_eq!(res.bookmark.channel_id, "C1");
+    }
+}
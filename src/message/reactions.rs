@@ -0,0 +1,361 @@
+//! 絵文字リアクションの追加・削除・取得
+
+use super::{pagination::paginate, pagination::ResponseMetadata, MessageClient};
+use crate::types::{ChannelId, FileId, Ts, UserId};
+use futures::Stream;
+use serde::Deserialize;
+use slack_morphism::prelude::*;
+use std::{error::Error, future::Future};
+use tracing::{info, warn};
+
+/// `reactions.list`で1回に取得する件数
+const LIST_PAGE_SIZE: u32 = 100;
+
+/// メッセージやファイルに付いたリアクション
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Reaction {
+    /// 絵文字の名前（コロンなし）
+    pub name: String,
+    /// リアクションしたユーザー数
+    pub count: usize,
+    /// リアクションしたユーザーのID
+    #[serde(default)]
+    pub users: Vec<UserId>,
+}
+
+impl From<SlackReaction> for Reaction {
+    fn from(reaction: SlackReaction) -> Self {
+        Self {
+            name: reaction.name.to_string(),
+            count: reaction.count,
+            users: reaction.users.into_iter().map(UserId::from).collect(),
+        }
+    }
+}
+
+/// `reactions.list`で取得した、リアクションの付いたアイテム
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReactedItem {
+    /// メッセージ
+    Message {
+        channel: ChannelId,
+        ts: Ts,
+        text: Option<String>,
+        user: Option<UserId>,
+        reactions: Vec<Reaction>,
+    },
+    /// ファイル
+    File {
+        id: FileId,
+        name: Option<String>,
+        reactions: Vec<Reaction>,
+    },
+    /// 未対応の種類のアイテム
+    Unknown(serde_json::Value),
+}
+
+impl ReactedItem {
+    /// アイテムに付いたリアクション
+    pub fn reactions(&self) -> &[Reaction] {
+        match self {
+            Self::Message { reactions, .. } | Self::File { reactions, .. } => reactions,
+            Self::Unknown(_) => &[],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListResponse {
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+    response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RawItem {
+    Message {
+        channel: ChannelId,
+        message: RawMessage,
+    },
+    File {
+        file: RawFile,
+    },
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    ts: Ts,
+    text: Option<String>,
+    user: Option<UserId>,
+    #[serde(default)]
+    reactions: Vec<Reaction>,
+}
+
+#[derive(Deserialize)]
+struct RawFile {
+    id: FileId,
+    name: Option<String>,
+    #[serde(default)]
+    reactions: Vec<Reaction>,
+}
+
+impl From<serde_json::Value> for ReactedItem {
+    fn from(value: serde_json::Value) -> Self {
+        match serde_json::from_value::<RawItem>(value.clone()) {
+            Ok(RawItem::Message { channel, message }) => Self::Message {
+                channel,
+                ts: message.ts,
+                text: message.text,
+                user: message.user,
+                reactions: message.reactions,
+            },
+            Ok(RawItem::File { file }) => Self::File {
+                id: file.id,
+                name: file.name,
+                reactions: file.reactions,
+            },
+            Err(_) => Self::Unknown(value),
+        }
+    }
+}
+
+/// [`MessageClient::with_status_reaction`]で使う絵文字
+#[derive(Debug, Clone)]
+pub struct StatusReactions {
+    /// 処理中に付ける絵文字
+    pub working: String,
+    /// 成功時に付ける絵文字
+    pub success: String,
+    /// 失敗時に付ける絵文字
+    pub failure: String,
+}
+
+impl Default for StatusReactions {
+    fn default() -> Self {
+        Self {
+            working: "eyes".to_string(),
+            success: "white_check_mark".to_string(),
+            failure: "x".to_string(),
+        }
+    }
+}
+
+/// `:name:`形式で指定された絵文字名からコロンを取り除きます
+fn reaction_name(name: &str) -> SlackReactionName {
+    SlackReactionName::new(name.trim_matches(':').to_string())
+}
+
+impl MessageClient {
+    /// メッセージにリアクションを追加します
+    ///
+    /// # 引数
+    /// * `name` - 絵文字の名前（`thumbsup`または`:thumbsup:`）
+    pub async fn add_reaction(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+        name: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let req = SlackApiReactionsAddRequest::new(
+            channel.clone().into(),
+            reaction_name(name),
+            ts.clone().into(),
+        );
+        let token = self.api_token("reactions.add");
+        let session = self.client.open_session(&token);
+        match session.reactions_add(&req).await {
+            Ok(_) => {
+                info!(
+                    "リアクションを追加しました: {} (ts: {}, name: {})",
+                    channel, ts, name
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!("リアクションの追加に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// メッセージからリアクションを削除します
+    pub async fn remove_reaction(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+        name: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let req = SlackApiReactionsRemoveRequest::new(reaction_name(name))
+            .with_channel(channel.clone().into())
+            .with_timestamp(ts.clone().into());
+        let token = self.api_token("reactions.remove");
+        let session = self.client.open_session(&token);
+        match session.reactions_remove(&req).await {
+            Ok(_) => {
+                info!(
+                    "リアクションを削除しました: {} (ts: {}, name: {})",
+                    channel, ts, name
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!("リアクションの削除に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// メッセージに付いたリアクションを取得します
+    pub async fn get_reactions(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+    ) -> Result<Vec<Reaction>, Box<dyn Error>> {
+        let channel = channel.into();
+        let req = SlackApiReactionsGetRequest::new()
+            .with_channel(channel.clone().into())
+            .with_timestamp(ts.clone().into())
+            .with_full(true);
+        let token = self.api_token("reactions.get");
+        let session = self.client.open_session(&token);
+        match session.reactions_get(&req).await {
+            Ok(SlackApiReactionsGetResponse::Message(res)) => Ok(res
+                .message
+                .content
+                .reactions
+                .unwrap_or_default()
+                .into_iter()
+                .map(Reaction::from)
+                .collect()),
+            Ok(SlackApiReactionsGetResponse::File(_)) => Ok(Vec::new()),
+            Err(e) => {
+                warn!("リアクションの取得に失敗しました: {:?}", e);
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// ユーザーがリアクションしたアイテムを取得します
+    ///
+    /// ページングは自動的に行われ、アイテムを1件ずつ返します。
+    ///
+    /// # 引数
+    /// * `user` - 対象のユーザーID（`None`の場合はトークンのユーザー）
+    pub fn list_reactions<'a>(
+        &'a self,
+        user: Option<&'a UserId>,
+    ) -> impl Stream<Item = Result<ReactedItem, Box<dyn Error>>> + 'a {
+        paginate(move |cursor| async move {
+            let params = vec![
+                ("cursor", cursor),
+                ("limit", Some(LIST_PAGE_SIZE.to_string())),
+                ("user", user.map(UserId::to_string)),
+                ("full", Some("true".to_string())),
+            ];
+            let res: ListResponse = self.api_get("reactions.list", &params).await?;
+            let next_cursor = ResponseMetadata::next_cursor(res.response_metadata);
+            Ok((
+                res.items.into_iter().map(ReactedItem::from).collect(),
+                next_cursor,
+            ))
+        })
+    }
+
+    /// 処理の状態をリアクションで表示しながら`task`を実行します
+    ///
+    /// 開始時に`working`を付け、完了時に外して`success`または`failure`を付けます。
+    /// リアクションの操作に失敗しても`task`の結果には影響しません。
+    pub async fn with_status_reaction<T, E, F>(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+        reactions: &StatusReactions,
+        task: F,
+    ) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        let channel = channel.into();
+        // 失敗はadd_reaction/remove_reaction内でログに出力済みのため、結果のみ確認する
+        let added = self
+            .add_reaction(&channel, ts, &reactions.working)
+            .await
+            .is_ok();
+        let result = task.await;
+        if added {
+            self.remove_reaction(&channel, ts, &reactions.working)
+                .await
+                .ok();
+        }
+        let name = if result.is_ok() {
+            &reactions.success
+        } else {
+            &reactions.failure
+        };
+        self.add_reaction(&channel, ts, name).await.ok();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reactions_list_items() {
+        let res: ListResponse = serde_json::from_value(serde_json::json!({
+            "ok": true,
+            "items": [
+                {
+                    "type": "message",
+                    "channel": "C1",
+                    "message": {
+                        "type": "message",
+                        "ts": "1700000000.000100",
+                        "text": "デプロイしました",
+                        "user": "U1",
+                        "reactions": [{"name": "tada", "count": 2, "users": ["U2", "U3"]}]
+                    }
+                },
+                {
+                    "type": "file",
+                    "file": {"id": "F1", "name": "log.txt", "reactions": [{"name": "eyes", "count": 1, "users": ["U2"]}]}
+                },
+                {"type": "file_comment", "comment": {}}
+            ],
+            "response_metadata": {"next_cursor": "dGVhbTpDMDYx"}
+        }))
+        .unwrap();
+
+        let items: Vec<ReactedItem> = res.items.into_iter().map(ReactedItem::from).collect();
+        assert_eq!(
+            items[0],
+            ReactedItem::Message {
+                channel: ChannelId::new("C1").unwrap(),
+                ts: Ts::new("1700000000.000100").unwrap(),
+                text: Some("デプロイしました".to_string()),
+                user: Some(UserId::new("U1").unwrap()),
+                reactions: vec![Reaction {
+                    name: "tada".to_string(),
+                    count: 2,
+                    users: vec![UserId::new("U2").unwrap(), UserId::new("U3").unwrap()],
+                }],
+            }
+        );
+        assert_eq!(items[1].reactions()[0].name, "eyes");
+        assert!(matches!(items[2], ReactedItem::Unknown(_)));
+        assert_eq!(
+            ResponseMetadata::next_cursor(res.response_metadata).as_deref(),
+            Some("dGVhbTpDMDYx")
+        );
+    }
+
+    #[test]
+    fn test_reaction_name_strips_colons() {
+        assert_eq!(reaction_name(":thumbsup:").to_string(), "thumbsup");
+        assert_eq!(reaction_name("eyes").to_string(), "eyes");
+    }
+}
@@ -0,0 +1,382 @@
+//! 逐次生成されるテキストをメッセージの更新で表示する
+
+use super::{MessageClient, RECOMMENDED_MESSAGE_LENGTH};
+use crate::{
+    mrkdwn,
+    types::{ChannelId, Ts},
+};
+use futures::{Stream, StreamExt};
+use slack_morphism::prelude::SlackMessageContent;
+use std::{error::Error, time::Duration};
+use tokio::time::{sleep_until, Instant};
+use tracing::warn;
+
+/// [`MessageStream`]の設定
+#[derive(Debug, Clone)]
+pub struct MessageStreamOptions {
+    /// 最初に投稿する仮のテキスト
+    pub placeholder: String,
+    /// `chat.update`を呼び出す最小間隔
+    ///
+    /// `chat.update`はTier 3（1分あたり50回程度）のため、1秒以上を推奨します。
+    pub update_interval: Duration,
+    /// 1メッセージあたりの最大文字数（超えた分はスレッドの新しいメッセージに続けます）
+    pub max_length: usize,
+    /// エラーで終了した場合に本文の末尾に追記するテキスト
+    pub error_notice: String,
+}
+
+impl Default for MessageStreamOptions {
+    fn default() -> Self {
+        Self {
+            placeholder: ":hourglass_flowing_sand:".to_string(),
+            update_interval: Duration::from_millis(1500),
+            max_length: RECOMMENDED_MESSAGE_LENGTH,
+            error_notice: ":warning: 応答の生成中にエラーが発生しました".to_string(),
+        }
+    }
+}
+
+/// 逐次生成されるテキストを1つ（長い場合は複数）のメッセージに反映するストリーム
+///
+/// 仮のメッセージを投稿し、[`push`](Self::push)で受け取ったテキストをまとめて
+/// 一定間隔ごとに`chat.update`で反映します。
+/// 最大文字数を超えた場合は、スレッドに新しいメッセージを投稿して続きを書き込みます。
+pub struct MessageStream {
+    client: MessageClient,
+    channel: ChannelId,
+    thread_ts: Option<Ts>,
+    options: MessageStreamOptions,
+    /// 投稿済みメッセージのタイムスタンプ（最後の要素が更新中のメッセージ）
+    timestamps: Vec<Ts>,
+    /// 更新中のメッセージの本文
+    text: String,
+    /// 未反映のテキストがあるか
+    dirty: bool,
+    /// 次に`chat.update`を呼び出せる時刻
+    next_update: Instant,
+}
+
+impl MessageStream {
+    /// 仮のメッセージを投稿してストリームを開始します
+    ///
+    /// # 引数
+    /// * `thread_ts` - スレッドに返信する場合は親メッセージのタイムスタンプ
+    pub async fn start(
+        client: &MessageClient,
+        channel: impl Into<ChannelId>,
+        thread_ts: Option<&Ts>,
+        options: MessageStreamOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        let channel = channel.into();
+        let content = SlackMessageContent::new().with_text(options.placeholder.clone());
+        let posted = client.post_message(&channel, content, thread_ts).await?;
+        Ok(Self {
+            client: client.clone(),
+            channel,
+            thread_ts: thread_ts.cloned(),
+            next_update: Instant::now() + options.update_interval,
+            options,
+            timestamps: vec![posted.ts],
+            text: String::new(),
+            dirty: false,
+        })
+    }
+
+    /// 投稿済みメッセージのタイムスタンプ（投稿順）
+    pub fn timestamps(&self) -> &[Ts] {
+        &self.timestamps
+    }
+
+    /// テキストを追加します
+    ///
+    /// 前回の更新から`update_interval`が経過していればメッセージに反映します。
+    pub async fn push(&mut self, chunk: &str) -> Result<(), Box<dyn Error>> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        self.text.push_str(chunk);
+        self.dirty = true;
+        self.overflow().await?;
+        if Instant::now() >= self.next_update {
+            self.flush().await;
+        }
+        Ok(())
+    }
+
+    /// 未反映のテキストがあれば、すぐにメッセージに反映します
+    ///
+    /// 更新に失敗した場合（レート制限など）は、次回の更新で再試行します。
+    pub async fn flush(&mut self) {
+        if !self.dirty || self.text.is_empty() {
+            return;
+        }
+        let ts = self.current_ts().clone();
+        match self
+            .client
+            .update_message(&self.channel, &ts, &self.text)
+            .await
+        {
+            Ok(()) => self.dirty = false,
+            Err(e) => warn!("ストリーミング中のメッセージ更新に失敗しました: {}", e),
+        }
+        self.next_update = Instant::now() + self.options.update_interval;
+    }
+
+    /// 残りのテキストを反映してストリームを終了します
+    ///
+    /// テキストが1文字も追加されなかった場合は、仮のメッセージを削除します。
+    ///
+    /// # 戻り値
+    /// 投稿したメッセージのタイムスタンプ（投稿順）
+    pub async fn finish(self) -> Result<Vec<Ts>, Box<dyn Error>> {
+        if self.text.is_empty() && self.timestamps.len() == 1 {
+            self.client
+                .delete_message(&self.channel, self.current_ts())
+                .await?;
+            return Ok(Vec::new());
+        }
+        self.wait_for_rate_limit().await;
+        let ts = self.current_ts().clone();
+        self.client
+            .update_message(&self.channel, &ts, &self.text)
+            .await?;
+        Ok(self.timestamps)
+    }
+
+    /// エラーの通知を追記してストリームを終了します
+    pub async fn abort(mut self) -> Result<Vec<Ts>, Box<dyn Error>> {
+        if !self.text.is_empty() {
+            self.text.push_str("\n\n");
+        }
+        self.text.push_str(&self.options.error_notice);
+        self.wait_for_rate_limit().await;
+        let ts = self.current_ts().clone();
+        self.client
+            .update_message(&self.channel, &ts, &self.text)
+            .await?;
+        Ok(self.timestamps)
+    }
+
+    fn current_ts(&self) -> &Ts {
+        self.timestamps
+            .last()
+            .expect("投稿済みのメッセージがありません")
+    }
+
+    async fn wait_for_rate_limit(&self) {
+        sleep_until(self.next_update).await;
+    }
+
+    /// 最大文字数を超えた部分をスレッドの新しいメッセージに移します
+    async fn overflow(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(mut parts) = split_overflow(&self.text, self.options.max_length) else {
+            return Ok(());
+        };
+        let rest = parts.pop().unwrap_or_default();
+        let thread_ts = self
+            .thread_ts
+            .clone()
+            .unwrap_or_else(|| self.timestamps[0].clone());
+
+        for (i, part) in parts.into_iter().enumerate() {
+            if i == 0 {
+                self.wait_for_rate_limit().await;
+                let ts = self.current_ts().clone();
+                self.client
+                    .update_message(&self.channel, &ts, &part)
+                    .await?;
+                self.next_update = Instant::now() + self.options.update_interval;
+            } else {
+                let content = SlackMessageContent::new().with_text(part);
+                let posted = self
+                    .client
+                    .post_message(&self.channel, content, Some(&thread_ts))
+                    .await?;
+                self.timestamps.push(posted.ts);
+            }
+        }
+
+        let content = SlackMessageContent::new().with_text(rest.clone());
+        let posted = self
+            .client
+            .post_message(&self.channel, content, Some(&thread_ts))
+            .await?;
+        self.timestamps.push(posted.ts);
+        self.text = rest;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// `max_length`を超えている場合に、確定する部分と続きの部分に分割します
+fn split_overflow(text: &str, max_length: usize) -> Option<Vec<String>> {
+    if text.chars().count() <= max_length {
+        return None;
+    }
+    let parts = mrkdwn::split(text, max_length);
+    (parts.len() > 1).then_some(parts)
+}
+
+impl MessageClient {
+    /// 逐次生成されるテキストをストリーミング表示します
+    ///
+    /// 仮のメッセージを投稿し、`chunks`から受け取ったテキストを
+    /// `options.update_interval`ごとにまとめて`chat.update`で反映します。
+    /// 最大文字数を超えた場合はスレッドの新しいメッセージに続けます。
+    /// メッセージの投稿に失敗した場合は、それまでの本文にエラーの通知を追記して終了します。
+    ///
+    /// # 引数
+    /// * `channel` - チャンネルID
+    /// * `thread_ts` - スレッドに返信する場合は親メッセージのタイムスタンプ
+    /// * `chunks` - 追記するテキストのストリーム
+    /// * `options` - 更新間隔などの設定
+    ///
+    /// # 戻り値
+    /// 投稿したメッセージのタイムスタンプ（投稿順）
+    pub async fn stream_message<S>(
+        &self,
+        channel: impl Into<ChannelId>,
+        thread_ts: Option<&Ts>,
+        chunks: S,
+        options: MessageStreamOptions,
+    ) -> Result<Vec<Ts>, Box<dyn Error>>
+    where
+        S: Stream<Item = String>,
+    {
+        let mut stream = MessageStream::start(self, channel, thread_ts, options).await?;
+        let mut chunks = std::pin::pin!(chunks);
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = chunks.next() => chunk,
+                // 新しいテキストが届かなくても、未反映の分は更新間隔ごとに反映する
+                _ = sleep_until(stream.next_update), if stream.dirty => {
+                    stream.flush().await;
+                    continue;
+                }
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            // `Box<dyn Error>`は`Send`ではないため、await前に文字列にしておく
+            if let Err(message) = stream.push(&chunk).await.map_err(|e| e.to_string()) {
+                warn!(
+                    "ストリーミング中のメッセージ投稿に失敗しました: {}",
+                    message
+                );
+                // 通知の追記に失敗しても、元のエラーを返す
+                if let Err(e) = stream.abort().await.map_err(|e| e.to_string()) {
+                    warn!("エラーの通知の追記に失敗しました: {}", e);
+                }
+                return Err(message.into());
+            }
+        }
+        stream.finish().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path, routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    /// 呼び出されたメソッドと本文を記録するWeb APIのスタブ
+    async fn stub_api() -> (String, Arc<Mutex<Vec<(String, String)>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let router = Router::new().route(
+            "/api/:method",
+            post(move |Path(method): Path<String>, Json(body): Json<Value>| {
+                let text = body["text"].as_str().unwrap_or_default().to_string();
+                recorded.lock().unwrap().push((method, text.clone()));
+                let ts = "1700000000.000100";
+                async move {
+                    Json(json!({
+                        "ok": true,
+                        "channel": "C1",
+                        "ts": ts,
+                        "message": {"ts": ts, "text": text}
+                    }))
+                }
+            }),
+        );
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let api_url = format!("http://{}/api", listener.local_addr().unwrap());
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+        (api_url, calls)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pushes_within_interval_are_coalesced() {
+        let (api_url, calls) = stub_api().await;
+        let client = MessageClient::new(crate::Token::new("xoxb-test"))
+            .unwrap()
+            .with_api_url(api_url);
+        let options = MessageStreamOptions {
+            update_interval: Duration::from_secs(2),
+            ..Default::default()
+        };
+        let mut stream =
+            MessageStream::start(&client, ChannelId::new("C1").unwrap(), None, options)
+                .await
+                .unwrap();
+        let updates = || {
+            calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(method, _)| method == "chat.update")
+                .map(|(_, text)| text.clone())
+                .collect::<Vec<_>>()
+        };
+
+        for chunk in ["a", "b", "c"] {
+            stream.push(chunk).await.unwrap();
+        }
+        assert!(updates().is_empty());
+
+        // 間隔が経過した後の最初のpushで、それまでの分をまとめて1回で反映する
+        tokio::time::advance(Duration::from_secs(2)).await;
+        stream.push("d").await.unwrap();
+        stream.push("e").await.unwrap();
+        assert_eq!(updates(), vec!["abcd"]);
+
+        stream.finish().await.unwrap();
+        assert_eq!(updates(), vec!["abcd", "abcde"]);
+        assert_eq!(calls.lock().unwrap()[0].0, "chat.postMessage");
+    }
+
+    #[test]
+    fn test_split_overflow_within_limit() {
+        assert_eq!(split_overflow("short text", 100), None);
+    }
+
+    #[test]
+    fn test_stream_message_is_send() {
+        fn assert_send<T: Send>(_: T) {}
+        let client = MessageClient::new(crate::Token::new("xoxb-test")).unwrap();
+        assert_send(client.stream_message(
+            ChannelId::new("C1").unwrap(),
+            None,
+            futures::stream::empty(),
+            MessageStreamOptions::default(),
+        ));
+    }
+
+    #[test]
+    fn test_split_overflow_on_paragraph() {
+        let text = format!("{}\n\n{}", "a".repeat(8), "b".repeat(8));
+        assert_eq!(
+            split_overflow(&text, 12),
+            Some(vec!["a".repeat(8), "b".repeat(8)])
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! 共有されたリンクのプレビュー（`chat.unfurl`）
+
+use super::MessageClient;
+use crate::{
+    blocks::Block,
+    types::{ChannelId, Ts},
+};
+use serde_json::{json, Map, Value};
+use std::{collections::HashMap, error::Error};
+use tracing::info;
+
+/// URLごとのプレビューを`chat.unfurl`の`unfurls`の形式に変換します
+fn unfurls_value(unfurls: &HashMap<String, Vec<Block>>) -> Value {
+    let unfurls: Map<String, Value> = unfurls
+        .iter()
+        .map(|(url, blocks)| (url.clone(), json!({ "blocks": blocks })))
+        .collect();
+    Value::Object(unfurls)
+}
+
+impl MessageClient {
+    /// メッセージに含まれるリンクのプレビューを設定します
+    ///
+    /// # 引数
+    /// * `channel` - `link_shared`イベントのチャンネルID
+    /// * `ts` - `link_shared`イベントの`message_ts`
+    /// * `unfurls` - URLごとのプレビューのブロック
+    pub async fn unfurl(
+        &self,
+        channel: impl Into<ChannelId>,
+        ts: &Ts,
+        unfurls: &HashMap<String, Vec<Block>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let channel = channel.into();
+        let body = json!({ "channel": channel, "ts": ts, "unfurls": unfurls_value(unfurls) });
+        let _: serde::de::IgnoredAny = self.api_post("chat.unfurl", &body).await?;
+        info!("リンクのプレビューを設定しました: {} (ts: {})", channel, ts);
+        Ok(())
+    }
+
+    /// `unfurl_id`と`source`を指定してリンクのプレビューを設定します
+    ///
+    /// メッセージ作成中（`source`が`composer`）のプレビューにはこちらを使用します。
+    pub async fn unfurl_by_id(
+        &self,
+        unfurl_id: &str,
+        source: &str,
+        unfurls: &HashMap<String, Vec<Block>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let body = json!({
+            "unfurl_id": unfurl_id,
+            "source": source,
+            "unfurls": unfurls_value(unfurls),
+        });
+        let _: serde::de::IgnoredAny = self.api_post("chat.unfurl", &body).await?;
+        info!("リンクのプレビューを設定しました: {}", unfurl_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextObject;
+
+    #[test]
+    fn test_unfurls_value() {
+        let unfurls = HashMap::from([(
+            "https://example.com/issues/1".to_string(),
+            vec![Block::section(TextObject::mrkdwn("*#1* ログインできない"))],
+        )]);
+        assert_eq!(
+            unfurls_value(&unfurls),
+            json!({
+                "https://example.com/issues/1": {"blocks": [
+                    {"type": "section", "text": {"type": "mrkdwn", "text": "*#1* ログインできない"}}
+                ]}
+            })
+        );
+    }
+}
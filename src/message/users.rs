@@ -0,0 +1,353 @@
+//! ユーザー情報の取得とキャッシュ
+
+use super::{
+    pagination::{paginate, ResponseMetadata},
+    MessageClient,
+};
+use crate::types::{TeamId, UserId};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::OnceCell, time::Instant};
+use tracing::info;
+
+/// 1回のリクエストで取得する件数の既定値
+const DEFAULT_PAGE_SIZE: u32 = 200;
+
+/// ワークスペースのユーザー
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct User {
+    /// ユーザーID
+    pub id: UserId,
+    pub team_id: Option<TeamId>,
+    /// ユーザー名（非推奨のハンドル名）
+    #[serde(default)]
+    pub name: String,
+    pub real_name: Option<String>,
+    /// 削除（無効化）されているか
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub is_bot: bool,
+    #[serde(default)]
+    pub is_admin: bool,
+    /// タイムゾーン（`Asia/Tokyo`など）
+    pub tz: Option<String>,
+    pub tz_label: Option<String>,
+    /// UTCからのオフセット（秒）
+    pub tz_offset: Option<i32>,
+    #[serde(default)]
+    pub profile: UserProfile,
+}
+
+impl User {
+    /// 表示名（未設定の場合は氏名、ユーザー名の順に使用します）
+    pub fn display_name(&self) -> &str {
+        [
+            self.profile.display_name.as_deref(),
+            self.profile.real_name.as_deref(),
+            self.real_name.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|name| !name.is_empty())
+        .unwrap_or(&self.name)
+    }
+
+    /// メールアドレス（`users:read.email`スコープが必要です）
+    pub fn email(&self) -> Option<&str> {
+        self.profile.email.as_deref()
+    }
+}
+
+/// ユーザーのプロフィール
+///
+/// `users.profile.set`では、`None`のフィールドは変更されません。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub real_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// 役職
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_text: Option<String>,
+    /// ステータスの絵文字（`:palm_tree:`など）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_emoji: Option<String>,
+    /// ステータスの有効期限（UNIX時間、0は無期限）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_expiration: Option<i64>,
+    /// 72x72のアイコン画像のURL
+    #[serde(skip_serializing)]
+    pub image_72: Option<String>,
+}
+
+/// ユーザーのオンライン状態
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Presence {
+    /// `active`または`away`
+    pub presence: String,
+    /// 以下はトークンのユーザー自身の状態を取得した場合のみ設定されます
+    pub online: Option<bool>,
+    pub auto_away: Option<bool>,
+    pub manual_away: Option<bool>,
+    pub last_activity: Option<i64>,
+}
+
+impl Presence {
+    /// アクティブか
+    pub fn is_active(&self) -> bool {
+        self.presence == "active"
+    }
+}
+
+#[derive(Deserialize)]
+struct UserResponse {
+    user: User,
+}
+
+#[derive(Deserialize)]
+struct ListResponse {
+    #[serde(default)]
+    members: Vec<User>,
+    response_metadata: Option<ResponseMetadata>,
+}
+
+#[derive(Deserialize)]
+struct ProfileResponse {
+    profile: UserProfile,
+}
+
+impl MessageClient {
+    /// ユーザーの情報を取得します
+    pub async fn user_info(&self, user: impl Into<UserId>) -> Result<User, Box<dyn Error>> {
+        let user = user.into();
+        let params = vec![("user", Some(user.to_string()))];
+        let res: UserResponse = self.api_get("users.info", &params).await?;
+        Ok(res.user)
+    }
+
+    /// ワークスペースのユーザーの一覧を取得します
+    ///
+    /// ページングは自動的に行われ、ユーザーを1件ずつ返します。
+    pub fn list_users(&self) -> impl Stream<Item = Result<User, Box<dyn Error>>> + '_ {
+        paginate(move |cursor| async move {
+            let params = vec![
+                ("limit", Some(DEFAULT_PAGE_SIZE.to_string())),
+                ("cursor", cursor),
+            ];
+            let res: ListResponse = self.api_get("users.list", &params).await?;
+            let next_cursor = ResponseMetadata::next_cursor(res.response_metadata);
+            Ok((res.members, next_cursor))
+        })
+    }
+
+    /// メールアドレスからユーザーを検索します
+    pub async fn lookup_user_by_email(&self, email: &str) -> Result<User, Box<dyn Error>> {
+        let params = vec![("email", Some(email.to_string()))];
+        let res: UserResponse = self.api_get("users.lookupByEmail", &params).await?;
+        Ok(res.user)
+    }
+
+    /// ユーザーのプロフィールを取得します
+    ///
+    /// # 引数
+    /// * `user` - ユーザーID（`None`の場合はトークンのユーザー）
+    pub async fn get_user_profile(
+        &self,
+        user: Option<&str>,
+    ) -> Result<UserProfile, Box<dyn Error>> {
+        let params = vec![("user", user.map(str::to_string))];
+        let res: ProfileResponse = self.api_get("users.profile.get", &params).await?;
+        Ok(res.profile)
+    }
+
+    /// ユーザーのプロフィールを更新します
+    ///
+    /// 他のユーザーのプロフィールを更新するには管理者のユーザートークンが必要です。
+    ///
+    /// # 引数
+    /// * `user` - ユーザーID（`None`の場合はトークンのユーザー）
+    /// * `profile` - 更新するフィールド（`None`のフィールドは変更されません）
+    pub async fn set_user_profile(
+        &self,
+        user: Option<&str>,
+        profile: &UserProfile,
+    ) -> Result<UserProfile, Box<dyn Error>> {
+        let mut body = json!({ "profile": profile });
+        if let Some(user) = user {
+            body["user"] = json!(user);
+        }
+        let res: ProfileResponse = self.api_post("users.profile.set", &body).await?;
+        info!("プロフィールを更新しました: {}", user.unwrap_or("(self)"));
+        Ok(res.profile)
+    }
+
+    /// ユーザーのオンライン状態を取得します
+    pub async fn get_presence(&self, user: impl Into<UserId>) -> Result<Presence, Box<dyn Error>> {
+        let user = user.into();
+        let params = vec![("user", Some(user.to_string()))];
+        self.api_get("users.getPresence", &params).await
+    }
+}
+
+type CacheEntries = HashMap<String, (Instant, Arc<OnceCell<User>>)>;
+
+/// ユーザー情報のキャッシュ
+///
+/// 取得したユーザーを`ttl`の間保持します。`Clone`しても同じキャッシュを共有するため、
+/// 複数のハンドラで使い回せます。同じユーザーへの同時の問い合わせは1回のAPI呼び出しにまとめられます。
+#[derive(Clone)]
+pub struct UserCache {
+    client: MessageClient,
+    ttl: Duration,
+    by_id: Arc<Mutex<CacheEntries>>,
+    by_email: Arc<Mutex<CacheEntries>>,
+}
+
+impl UserCache {
+    /// キャッシュを作成します
+    ///
+    /// # 引数
+    /// * `ttl` - ユーザー情報を保持する期間
+    pub fn new(client: MessageClient, ttl: Duration) -> Self {
+        Self {
+            client,
+            ttl,
+            by_id: Arc::default(),
+            by_email: Arc::default(),
+        }
+    }
+
+    /// ユーザーの情報を取得します（キャッシュがなければ`users.info`を呼び出します）
+    pub async fn get(&self, user: impl Into<UserId>) -> Result<User, Box<dyn Error>> {
+        let user = user.into();
+        let cell = self.entry(&self.by_id, user.as_str());
+        let user = cell
+            .get_or_try_init(|| async {
+                // `Box<dyn Error>`は`Send`ではないため、文字列にして返す
+                self.client
+                    .user_info(&user)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+        Ok(user.clone())
+    }
+
+    /// メールアドレスからユーザーを取得します（キャッシュがなければ`users.lookupByEmail`を呼び出します）
+    pub async fn get_by_email(&self, email: &str) -> Result<User, Box<dyn Error>> {
+        let cell = self.entry(&self.by_email, email);
+        let user = cell
+            .get_or_try_init(|| async {
+                self.client
+                    .lookup_user_by_email(email)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+            .await?
+            .clone();
+        self.insert(user.clone());
+        Ok(user)
+    }
+
+    /// ユーザーの情報をキャッシュに追加します（`users.list`の結果の取り込みなど）
+    pub fn insert(&self, user: User) {
+        let cell = Arc::new(OnceCell::new_with(Some(user.clone())));
+        lock(&self.by_id).insert(user.id.into_inner(), (Instant::now(), cell));
+    }
+
+    /// ユーザーのキャッシュを破棄します（`user_change`イベントを受け取った場合など）
+    pub fn invalidate(&self, user: impl Into<UserId>) {
+        let user = user.into();
+        lock(&self.by_id).remove(user.as_str());
+        lock(&self.by_email)
+            .retain(|_, (_, cell)| cell.get().map_or(true, |u| u.id.as_str() != user.as_str()));
+    }
+
+    /// すべてのキャッシュを破棄します
+    pub fn clear(&self) {
+        lock(&self.by_id).clear();
+        lock(&self.by_email).clear();
+    }
+
+    /// 有効期限内のエントリを返し、なければ新しいエントリを作成します
+    fn entry(&self, entries: &Mutex<CacheEntries>, key: &str) -> Arc<OnceCell<User>> {
+        let mut entries = lock(entries);
+        let now = Instant::now();
+        entries.retain(|_, (created, _)| now.duration_since(*created) < self.ttl);
+        entries
+            .entry(key.to_string())
+            .or_insert_with(|| (now, Arc::new(OnceCell::new())))
+            .1
+            .clone()
+    }
+}
+
+fn lock(entries: &Mutex<CacheEntries>) -> std::sync::MutexGuard<'_, CacheEntries> {
+    // 保持中にパニックしても内容は壊れないため、そのまま使う
+    entries.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str) -> User {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": "taro",
+            "real_name": "山田 太郎",
+            "tz": "Asia/Tokyo",
+            "tz_offset": 32400,
+            "profile": {"display_name": "", "real_name": "山田 太郎", "email": "taro@example.com"}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_user() {
+        let user = user("U1");
+        assert_eq!(user.display_name(), "山田 太郎");
+        assert_eq!(user.email(), Some("taro@example.com"));
+        assert_eq!(user.tz_offset, Some(32400));
+        assert!(!user.is_bot);
+    }
+
+    #[tokio::test]
+    async fn test_cache_returns_inserted_user_without_api_call() {
+        let cache = UserCache::new(
+            MessageClient::new(crate::Token::new("xoxb-test")).unwrap(),
+            Duration::from_secs(60),
+        );
+        cache.insert(user("U1"));
+        let id = UserId::new("U1").unwrap();
+        assert_eq!(cache.get(&id).await.unwrap().id, "U1");
+
+        cache.invalidate(&id);
+        assert!(!lock(&cache.by_id).contains_key("U1"));
+    }
+
+    #[test]
+    fn test_cache_get_is_send() {
+        fn assert_send<T: Send>(_: T) {}
+        let cache = UserCache::new(
+            MessageClient::new(crate::Token::new("xoxb-test")).unwrap(),
+            Duration::from_secs(60),
+        );
+        assert_send(cache.get(UserId::new("U1").unwrap()));
+    }
+}
@@ -0,0 +1,151 @@
+//! モーダルの表示・更新とホームタブの公開
+
+use super::MessageClient;
+use crate::{
+    blocks::{View, ViewState},
+    types::{AppId, TeamId, UserId},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::error::Error;
+use tracing::info;
+
+/// 表示・更新されたビューの情報
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ViewInfo {
+    /// ビューID（`update_view`で指定します）
+    pub id: String,
+    /// 更新の競合を検出するためのハッシュ（`update_view`・`publish_view`で指定します）
+    #[serde(default)]
+    pub hash: String,
+    pub team_id: Option<TeamId>,
+    pub app_id: Option<AppId>,
+    pub callback_id: Option<String>,
+    pub private_metadata: Option<String>,
+    pub external_id: Option<String>,
+    /// スタックの最初のモーダルのID
+    pub root_view_id: Option<String>,
+    /// 1つ前のモーダルのID
+    pub previous_view_id: Option<String>,
+    /// 現在の入力値
+    #[serde(default)]
+    pub state: ViewState,
+}
+
+#[derive(Deserialize)]
+struct ViewResponse {
+    view: ViewInfo,
+}
+
+impl MessageClient {
+    /// モーダルを開きます
+    ///
+    /// ビューがSlackの上限を超えている場合は、送信せずに[`InvalidView`](crate::blocks::InvalidView)を返します。
+    ///
+    /// # 引数
+    /// * `trigger_id` - スラッシュコマンドやボタン操作で受け取ったトリガーID（3秒間有効）
+    pub async fn open_view(
+        &self,
+        trigger_id: &str,
+        view: &View,
+    ) -> Result<ViewInfo, Box<dyn Error>> {
+        view.validate()?;
+        let res: ViewResponse = self
+            .api_post(
+                "views.open",
+                &json!({ "trigger_id": trigger_id, "view": view }),
+            )
+            .await?;
+        info!("モーダルを開きました: {}", res.view.id);
+        Ok(res.view)
+    }
+
+    /// 表示中のモーダルの上に新しいモーダルを重ねます
+    pub async fn push_view(
+        &self,
+        trigger_id: &str,
+        view: &View,
+    ) -> Result<ViewInfo, Box<dyn Error>> {
+        view.validate()?;
+        let res: ViewResponse = self
+            .api_post(
+                "views.push",
+                &json!({ "trigger_id": trigger_id, "view": view }),
+            )
+            .await?;
+        info!("モーダルを重ねて開きました: {}", res.view.id);
+        Ok(res.view)
+    }
+
+    /// モーダルを更新します
+    ///
+    /// `hash`を指定すると、取得後に他の操作でビューが更新されていた場合は
+    /// `hash_conflict`エラーになり、古い内容での上書きを防げます。
+    pub async fn update_view(
+        &self,
+        view_id: &str,
+        view: &View,
+        hash: Option<&str>,
+    ) -> Result<ViewInfo, Box<dyn Error>> {
+        view.validate()?;
+        let mut body = json!({ "view_id": view_id, "view": view });
+        if let Some(hash) = hash {
+            body["hash"] = json!(hash);
+        }
+        let res: ViewResponse = self.api_post("views.update", &body).await?;
+        info!("モーダルを更新しました: {}", view_id);
+        Ok(res.view)
+    }
+
+    /// ユーザーのホームタブを公開します
+    ///
+    /// `hash`の扱いは[`update_view`](Self::update_view)と同じです。
+    pub async fn publish_view(
+        &self,
+        user_id: impl Into<UserId>,
+        view: &View,
+        hash: Option<&str>,
+    ) -> Result<ViewInfo, Box<dyn Error>> {
+        let user_id = user_id.into();
+        view.validate()?;
+        let mut body = json!({ "user_id": user_id, "view": view });
+        if let Some(hash) = hash {
+            body["hash"] = json!(hash);
+        }
+        let res: ViewResponse = self.api_post("views.publish", &body).await?;
+        info!("ホームタブを公開しました: {}", user_id);
+        Ok(res.view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_view_response() {
+        let res: ViewResponse = serde_json::from_value(json!({
+            "ok": true,
+            "view": {
+                "id": "V0123456789",
+                "team_id": "T1",
+                "type": "modal",
+                "title": {"type": "plain_text", "text": "障害報告", "emoji": true},
+                "blocks": [],
+                "private_metadata": "C1",
+                "callback_id": "incident",
+                "state": {"values": {}},
+                "hash": "156772938.1827394",
+                "root_view_id": "V0123456789",
+                "previous_view_id": null,
+                "app_id": "A1"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(res.view.id, "V0123456789");
+        assert_eq!(res.view.hash, "156772938.1827394");
+        assert_eq!(res.view.private_metadata.as_deref(), Some("C1"));
+        assert!(res.view.state.values.is_empty());
+    }
+}
@@ -0,0 +1,498 @@
+//! Slackのmrkdwn形式のテキストを扱うモジュール
+//!
+//! ユーザー入力をそのまま送信すると`&`、`<`、`>`が制御文字として解釈され、
+//! `<!channel>`のような文字列で意図しない全体通知が発生します。
+//! 外部から受け取ったテキストは[`escape`]してから送信してください。
+//!
+//! メンションやリンクは[`Entity`]で組み立て、受信したテキストからは[`parse`]で取り出せます。
+
+use crate::types::{ChannelId, UserId, UsergroupId};
+use std::fmt;
+
+/// mrkdwnの制御文字（`&`、`<`、`>`）をエスケープします。
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// リンク先のURLを`<…>`に埋め込めるようエスケープします。
+///
+/// 制御文字に加えて、表示テキストとの区切りになる`|`をパーセントエンコードします。
+pub fn escape_url(url: &str) -> String {
+    escape(url).replace('|', "%7C")
+}
+
+/// [`escape`]されたテキストを元に戻します。
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// 全体通知の範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Broadcast {
+    /// `@here`: オンラインのメンバー
+    Here,
+    /// `@channel`: チャンネルの全メンバー
+    Channel,
+    /// `@everyone`: ワークスペースの全メンバー
+    Everyone,
+}
+
+impl Broadcast {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Here => "here",
+            Self::Channel => "channel",
+            Self::Everyone => "everyone",
+        }
+    }
+}
+
+/// mrkdwn内のメンションやリンク
+///
+/// `Display`で送信用の文字列に変換できます。
+///
+/// ```
+/// use slack_rs::{mrkdwn::Entity, UserId};
+///
+/// let user = UserId::new("U012AB3CD").unwrap();
+/// let text = format!(
+///     "{} さん、{} を確認してください",
+///     Entity::user(&user),
+///     Entity::link("https://example.com", "手順書"),
+/// );
+/// assert_eq!(
+///     text,
+///     "<@U012AB3CD> さん、<https://example.com|手順書> を確認してください"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entity {
+    /// ユーザーメンション（`<@U…>`）
+    User {
+        /// ユーザーID
+        id: UserId,
+        /// 表示名（受信したテキストに含まれる場合のみ）
+        label: Option<String>,
+    },
+    /// チャンネルリンク（`<#C…>`）
+    Channel {
+        /// チャンネルID
+        id: ChannelId,
+        /// チャンネル名（受信したテキストに含まれる場合のみ）
+        label: Option<String>,
+    },
+    /// ユーザーグループメンション（`<!subteam^S…>`）
+    Usergroup {
+        /// ユーザーグループID
+        id: UsergroupId,
+        /// ハンドル名（受信したテキストに含まれる場合のみ）
+        label: Option<String>,
+    },
+    /// 全体通知（`<!here>`など）
+    Broadcast(Broadcast),
+    /// リンク（`<url|label>`）
+    Link {
+        /// リンク先URL
+        url: String,
+        /// 表示テキスト
+        label: Option<String>,
+    },
+    /// 閲覧者のタイムゾーンで表示される日付（`<!date^…^{date_short}|fallback>`）
+    Date {
+        /// UNIXタイムスタンプ（秒）
+        timestamp: i64,
+        /// 表示形式（例：`{date_short} {time}`）
+        format: String,
+        /// クリック時に開くURL
+        link: Option<String>,
+        /// 表示できない場合の代替テキスト
+        fallback: String,
+    },
+}
+
+impl Entity {
+    /// ユーザーメンションを作成
+    pub fn user(id: &UserId) -> Self {
+        Self::User {
+            id: id.clone(),
+            label: None,
+        }
+    }
+
+    /// チャンネルリンクを作成
+    pub fn channel(id: &ChannelId) -> Self {
+        Self::Channel {
+            id: id.clone(),
+            label: None,
+        }
+    }
+
+    /// ユーザーグループメンションを作成
+    pub fn usergroup(id: &UsergroupId) -> Self {
+        Self::Usergroup {
+            id: id.clone(),
+            label: None,
+        }
+    }
+
+    /// `@here`を作成
+    pub fn here() -> Self {
+        Self::Broadcast(Broadcast::Here)
+    }
+
+    /// 表示テキスト付きのリンクを作成
+    pub fn link(url: impl Into<String>, label: impl Into<String>) -> Self {
+        Self::Link {
+            url: url.into(),
+            label: Some(label.into()),
+        }
+    }
+
+    /// 日付を作成
+    ///
+    /// `format`には`{date_short}`、`{date_long}`、`{time}`などのトークンを指定します。
+    pub fn date(timestamp: i64, format: impl Into<String>, fallback: impl Into<String>) -> Self {
+        Self::Date {
+            timestamp,
+            format: format.into(),
+            link: None,
+            fallback: fallback.into(),
+        }
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User { id, .. } => write!(f, "<@{}>", id),
+            Self::Channel { id, .. } => write!(f, "<#{}>", id),
+            Self::Usergroup { id, .. } => write!(f, "<!subteam^{}>", id),
+            Self::Broadcast(range) => write!(f, "<!{}>", range.as_str()),
+            Self::Link { url, label: None } => write!(f, "<{}>", escape_url(url)),
+            Self::Link {
+                url,
+                label: Some(label),
+            } => write!(f, "<{}|{}>", escape_url(url), escape(label)),
+            Self::Date {
+                timestamp,
+                format,
+                link,
+                fallback,
+            } => {
+                write!(f, "<!date^{}^{}", timestamp, format)?;
+                if let Some(link) = link {
+                    write!(f, "^{}", escape_url(link))?;
+                }
+                write!(f, "|{}>", escape(fallback))
+            }
+        }
+    }
+}
+
+/// テキストに含まれるメンションやリンクを出現順に取り出します。
+///
+/// 解釈できない`<…>`や、IDの形式が正しくないメンションは無視されます。
+pub fn parse(text: &str) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        if let Some(entity) = parse_entity(&rest[start + 1..start + len]) {
+            entities.push(entity);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    entities
+}
+
+fn parse_entity(inner: &str) -> Option<Entity> {
+    let (body, label) = match inner.split_once('|') {
+        Some((body, label)) => (body, Some(unescape(label))),
+        None => (inner, None),
+    };
+    if let Some(id) = body.strip_prefix('@') {
+        return Some(Entity::User {
+            id: id.parse().ok()?,
+            label,
+        });
+    }
+    if let Some(id) = body.strip_prefix('#') {
+        return Some(Entity::Channel {
+            id: id.parse().ok()?,
+            label,
+        });
+    }
+    if let Some(command) = body.strip_prefix('!') {
+        let mut parts = command.split('^');
+        return match parts.next()? {
+            "here" => Some(Entity::Broadcast(Broadcast::Here)),
+            "channel" => Some(Entity::Broadcast(Broadcast::Channel)),
+            "everyone" => Some(Entity::Broadcast(Broadcast::Everyone)),
+            "subteam" => Some(Entity::Usergroup {
+                id: parts.next()?.parse().ok()?,
+                label,
+            }),
+            "date" => Some(Entity::Date {
+                timestamp: parts.next()?.parse().ok()?,
+                format: parts.next()?.to_string(),
+                link: parts.next().map(str::to_string),
+                fallback: label.unwrap_or_default(),
+            }),
+            _ => None,
+        };
+    }
+    if body.contains(':') {
+        return Some(Entity::Link {
+            url: unescape(body),
+            label,
+        });
+    }
+    None
+}
+
+/// `limit`文字以内になるよう、mrkdwnのテキストを分割します。
+///
+/// 段落（空行）、行、空白の順に区切りを探し、`<…>`のメンションやリンクの途中では分割しません。
+/// コードブロック（```` ``` ````）の途中で分割する場合は、前半を閉じて後半で開き直し、
+/// 行頭のインデントはそのまま残します。`limit`が0の場合は1として扱います。
+pub fn split(text: &str, limit: usize) -> Vec<String> {
+    const FENCE: &str = "```";
+    let limit = limit.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text.to_string();
+
+    while rest.chars().count() > limit {
+        let protected = entity_ranges(&rest);
+        let find_cut = |budget: usize| {
+            let end = rest
+                .char_indices()
+                .nth(budget)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            let allowed = |i: usize| i > 0 && !protected.iter().any(|r| r.start < i && i < r.end);
+            ["\n\n", "\n", " "]
+                .iter()
+                .find_map(|sep| {
+                    rest[..end]
+                        .match_indices(sep)
+                        .map(|(i, _)| i)
+                        .filter(|&i| allowed(i))
+                        .last()
+                })
+                .or_else(|| {
+                    rest[..end]
+                        .char_indices()
+                        .map(|(i, _)| i)
+                        .filter(|&i| allowed(i))
+                        .last()
+                })
+                .unwrap_or(end)
+        };
+
+        let mut cut = find_cut(limit);
+        if rest[..cut].matches(FENCE).count() % 2 == 1 {
+            // コードブロックを閉じるための余白を残して区切り直す
+            let budget = limit.saturating_sub(FENCE.len() + 1).max(1);
+            cut = find_cut(budget);
+            // 開始行の末尾以前で区切ると同じ入力が残って進まなくなるため、
+            // 区切りがなければ文字単位で区切る
+            if let Some(open) = rest[..cut].rfind(FENCE) {
+                let content = rest[open..].find('\n').map_or(rest.len(), |i| open + i + 1);
+                if cut <= content {
+                    let hard = rest
+                        .char_indices()
+                        .nth(budget)
+                        .map_or(rest.len(), |(i, _)| i);
+                    cut = if hard > content {
+                        hard
+                    } else {
+                        rest[content..]
+                            .char_indices()
+                            .nth(1)
+                            .map_or(rest.len(), |(i, _)| content + i)
+                    };
+                }
+            }
+        }
+
+        let in_fence = rest[..cut].matches(FENCE).count() % 2 == 1;
+        let (mut chunk, mut next) = if in_fence {
+            // コードブロック内では区切りの改行だけを取り除く
+            let next = &rest[cut..];
+            (
+                rest[..cut].trim_end_matches('\n').to_string(),
+                next.strip_prefix('\n').unwrap_or(next).to_string(),
+            )
+        } else {
+            (
+                rest[..cut].trim_end().to_string(),
+                rest[cut..].trim_start_matches(['\n', ' ']).to_string(),
+            )
+        };
+        if in_fence && !next.is_empty() {
+            chunk.push('\n');
+            chunk.push_str(FENCE);
+            next = format!("{}\n{}", FENCE, next);
+        }
+        chunks.push(chunk);
+        rest = next;
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+/// `<…>`で囲まれた範囲（バイト位置）
+fn entity_ranges(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find('<') {
+        let start = offset + start;
+        let Some(len) = text[start..].find('>') else {
+            break;
+        };
+        ranges.push(start..start + len + 1);
+        offset = start + len + 1;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a & b <!channel> c"), "a &amp; b &lt;!channel&gt; c");
+        assert_eq!(unescape(&escape("<&>")), "<&>");
+    }
+
+    #[test]
+    fn test_format_entities() {
+        let user = UserId::new("U1").unwrap();
+        let channel = ChannelId::new("C1").unwrap();
+        let usergroup = UsergroupId::new("S1").unwrap();
+        assert_eq!(Entity::user(&user).to_string(), "<@U1>");
+        assert_eq!(Entity::channel(&channel).to_string(), "<#C1>");
+        assert_eq!(Entity::usergroup(&usergroup).to_string(), "<!subteam^S1>");
+        assert_eq!(Entity::here().to_string(), "<!here>");
+        assert_eq!(
+            Entity::Broadcast(Broadcast::Everyone).to_string(),
+            "<!everyone>"
+        );
+        assert_eq!(
+            Entity::link("https://example.com", "a <b>").to_string(),
+            "<https://example.com|a &lt;b&gt;>"
+        );
+        assert_eq!(
+            Entity::date(1392734382, "{date_short}", "Feb 18, 2014").to_string(),
+            "<!date^1392734382^{date_short}|Feb 18, 2014>"
+        );
+    }
+
+    #[test]
+    fn test_split_on_paragraphs_and_lines() {
+        let text = "aaaa bbbb\n\ncccc\ndddd";
+        assert_eq!(split(text, 12), vec!["aaaa bbbb", "cccc\ndddd"]);
+        assert_eq!(split("short", 12), vec!["short"]);
+    }
+
+    #[test]
+    fn test_split_does_not_break_entities() {
+        let text = "word word <https://ex.com|docs> tail";
+        assert_eq!(
+            split(text, 24),
+            vec!["word word", "<https://ex.com|docs>", "tail"]
+        );
+    }
+
+    #[test]
+    fn test_split_reopens_code_fences() {
+        let text = format!("intro\n```\n{}```", "let x = 1;\n".repeat(10));
+        let chunks = split(&text, 60);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 60);
+            assert_eq!(chunk.matches("```").count() % 2, 0, "{}", chunk);
+        }
+        assert!(chunks[1].starts_with("```\n"));
+    }
+
+    #[test]
+    fn test_split_keeps_indentation_in_code_fences() {
+        let text = "```\nfn main() {\n    let a = 1;\n    let b = 2;\n}\n```";
+        let chunks = split(text, 30);
+        assert_eq!(
+            chunks,
+            vec![
+                "```\nfn main() {\n```",
+                "```\n    let a = 1;\n```",
+                "```\n    let b = 2;\n}\n```"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_long_first_line_in_code_fence() {
+        for (limit, line) in [(10, "x".repeat(20)), (4000, "QUJD".repeat(1500))] {
+            let text = format!("```\n{}\n```", line);
+            let chunks = split(&text, limit);
+            let mut content = String::new();
+            for chunk in &chunks {
+                assert!(chunk.chars().count() <= limit, "{}", chunk);
+                let inner = chunk.strip_prefix("```\n").unwrap();
+                content.push_str(inner.strip_suffix("\n```").unwrap());
+            }
+            assert_eq!(content, line);
+        }
+        // 空白を含む長い行でも進む
+        let chunks = split("```\naaaa bbbb cccc dddd\n```", 10);
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 10));
+    }
+
+    #[test]
+    fn test_split_zero_limit_terminates() {
+        assert_eq!(split("ab c", 0), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_parse_entities() {
+        let text = "<@U012AB3CD> see <#C123|general>, <!subteam^S1|@oncall> <!here> \
+                    <https://example.com/?a=1&amp;b=2|docs &amp; more> <mailto:a@example.com> \
+                    <!date^1392734382^{date_short}^https://example.com|Feb 18> &lt;not a link&gt; \
+                    <@not-an-id>";
+        assert_eq!(
+            parse(text),
+            vec![
+                Entity::user(&UserId::new("U012AB3CD").unwrap()),
+                Entity::Channel {
+                    id: ChannelId::new("C123").unwrap(),
+                    label: Some("general".to_string()),
+                },
+                Entity::Usergroup {
+                    id: UsergroupId::new("S1").unwrap(),
+                    label: Some("@oncall".to_string()),
+                },
+                Entity::here(),
+                Entity::link("https://example.com/?a=1&b=2", "docs & more"),
+                Entity::Link {
+                    url: "mailto:a@example.com".to_string(),
+                    label: None,
+                },
+                Entity::Date {
+                    timestamp: 1392734382,
+                    format: "{date_short}".to_string(),
+                    link: Some("https://example.com".to_string()),
+                    fallback: "Feb 18".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! レート制限と再試行の設定
+
+use slack_morphism::prelude::SlackApiRateControlConfig;
+use std::time::Duration;
+
+/// レート制限（HTTP 429）やサーバーエラー時の再試行の設定
+///
+/// [`MessageClient`](crate::MessageClient)と
+/// [`IncomingWebhookClient`](crate::IncomingWebhookClient)で共通して使用しますが、
+/// 再試行する条件はクライアントによって異なります。
+///
+/// | | `MessageClient`（Web API） | `IncomingWebhookClient` |
+/// |---|---|---|
+/// | レート制限（HTTP 429） | `Retry-After`に従って再試行 | `Retry-After`に従って再試行 |
+/// | サーバーエラー（HTTP 5xx）・通信エラー | 再試行しない | `initial_delay`から倍々で待って再試行 |
+/// | 使用するフィールド | `max_retries`、`max_delay` | すべて |
+///
+/// Web APIの再試行はslack-morphismのレート制御に任せており、
+/// slack-morphismはレート制限以外のエラーを再試行しないためです。
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// 最大再試行回数
+    pub max_retries: usize,
+    /// `Retry-After`が指定されていない場合の最初の待ち時間（再試行ごとに2倍にします）
+    ///
+    /// Web APIはレート制限の場合にしか再試行せず、常に`Retry-After`に従うため、
+    /// [`MessageClient`](crate::MessageClient)では使用しません。
+    pub initial_delay: Duration,
+    /// 1回あたりの最大待ち時間（これより長い待ちが必要な場合は再試行しません）
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// 再試行しない設定
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// `attempt`回目（0始まり）の再試行までの待ち時間
+    ///
+    /// `retry_after`が指定されている場合はそれに従います。
+    /// 再試行しない場合は`None`を返します。
+    pub(crate) fn delay(&self, attempt: usize, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        let delay = retry_after.unwrap_or_else(|| {
+            self.initial_delay
+                .saturating_mul(1u32 << attempt.min(16))
+                .min(self.max_delay)
+        });
+        (delay <= self.max_delay).then_some(delay)
+    }
+
+    /// slack-morphismのレート制御の設定（`initial_delay`は対応する設定がないため使用しません）
+    pub(crate) fn rate_control(&self) -> SlackApiRateControlConfig {
+        SlackApiRateControlConfig::new()
+            .with_max_retries(self.max_retries)
+            .with_max_delay_timeout(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_backoff_and_retry_after() {
+        let config = RetryConfig::default();
+        assert_eq!(config.delay(0, None), Some(Duration::from_secs(1)));
+        assert_eq!(config.delay(2, None), Some(Duration::from_secs(4)));
+        assert_eq!(
+            config.delay(0, Some(Duration::from_secs(30))),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(config.delay(0, Some(Duration::from_secs(120))), None);
+        assert_eq!(config.delay(3, None), None);
+        assert_eq!(RetryConfig::none().delay(0, None), None);
+    }
+}
@@ -0,0 +1,213 @@
+//! A local server speaking the Socket Mode protocol, for testing the client without Slack.
+//!
+//! It serves an `apps.connections.open` stub over HTTP and a WebSocket endpoint that greets
+//! every connection with `hello`. Tests drive each connection through [`FakeConnection`]:
+//! they send envelopes and assert on the acknowledgements the client sends back.
+//!
+//! Available to other crates with the `testing` feature. Being meant for tests, its methods
+//! panic instead of returning errors when the client misbehaves or does not respond in time.
+//!
+//! ```no_run
+//! use serde_json::json;
+//! use slack_rs::socket_mode::{FakeSocketModeServer, SocketModeEvent};
+//! use tokio::sync::mpsc;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut server = FakeSocketModeServer::start().await;
+//! let (sender, _events) = mpsc::unbounded_channel::<SocketModeEvent>();
+//! let handle = server.client().connect_with(sender).await?;
+//! let mut connection = server.accept().await;
+//!
+//! connection.send_event("events_api", "e1", json!({ /* event_callback */ })).await;
+//! assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e1"}));
+//! handle.shutdown().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::SocketModeClient;
+use axum::{
+    http::{header::AUTHORIZATION, HeaderMap},
+    routing::post,
+    Json, Router,
+};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+
+/// The only app token the stub accepts; any other gets `invalid_auth`.
+pub const APP_TOKEN: &str = "xapp-1-A1-test";
+
+/// How long to wait for the client before failing the test.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A fake Socket Mode server listening on random local ports.
+pub struct FakeSocketModeServer {
+    api_url: String,
+    opened: Arc<AtomicUsize>,
+    connections: mpsc::UnboundedReceiver<FakeConnection>,
+}
+
+impl FakeSocketModeServer {
+    /// Starts the server in the background.
+    pub async fn start() -> Self {
+        let ws_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}/link", ws_listener.local_addr().unwrap());
+        let (sender, connections) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = ws_listener.accept().await {
+                let Ok(socket) = tokio_tungstenite::accept_async(stream).await else {
+                    continue;
+                };
+                let mut connection = FakeConnection { socket };
+                connection
+                    .send(json!({"type": "hello", "num_connections": 1}))
+                    .await;
+                if sender.send(connection).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let opened = Arc::new(AtomicUsize::new(0));
+        let counter = opened.clone();
+        let router = Router::new().route(
+            "/api/apps.connections.open",
+            post(move |headers: HeaderMap| {
+                let counter = counter.clone();
+                let ws_url = ws_url.clone();
+                async move {
+                    let authorized = headers
+                        .get(AUTHORIZATION)
+                        .and_then(|value| value.to_str().ok())
+                        == Some(format!("Bearer {}", APP_TOKEN).as_str());
+                    if !authorized {
+                        return Json(json!({"ok": false, "error": "invalid_auth"}));
+                    }
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Json(json!({"ok": true, "url": ws_url}))
+                }
+            }),
+        );
+        let http_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        http_listener.set_nonblocking(true).unwrap();
+        let api_url = format!("http://{}/api", http_listener.local_addr().unwrap());
+        tokio::spawn(
+            axum::Server::from_tcp(http_listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        Self {
+            api_url,
+            opened,
+            connections,
+        }
+    }
+
+    /// Base URL of the stubbed Web API.
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// A client pointed at this server with the accepted app token.
+    pub fn client(&self) -> SocketModeClient {
+        SocketModeClient::new(APP_TOKEN)
+            .unwrap()
+            .with_api_url(self.api_url())
+    }
+
+    /// Number of successful `apps.connections.open` calls so far.
+    pub fn opened(&self) -> usize {
+        self.opened.load(Ordering::SeqCst)
+    }
+
+    /// Waits for the client to open the next WebSocket connection.
+    pub async fn accept(&mut self) -> FakeConnection {
+        tokio::time::timeout(TIMEOUT, self.connections.recv())
+            .await
+            .expect("クライアントが接続しませんでした")
+            .unwrap()
+    }
+}
+
+/// The server side of one WebSocket connection.
+pub struct FakeConnection {
+    socket: WebSocketStream<TcpStream>,
+}
+
+impl FakeConnection {
+    /// Sends a raw Socket Mode message.
+    pub async fn send(&mut self, message: Value) {
+        self.socket
+            .send(Message::text(message.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// Sends an `events_api`, `slash_commands` or `interactive` envelope.
+    pub async fn send_event(&mut self, kind: &str, envelope_id: &str, payload: Value) {
+        self.send(json!({
+            "type": kind,
+            "envelope_id": envelope_id,
+            "payload": payload,
+            "accepts_response_payload": kind == "slash_commands",
+            "retry_attempt": 0,
+            "retry_reason": "",
+        }))
+        .await;
+    }
+
+    /// Sends the warning Slack gives shortly before refreshing a connection.
+    pub async fn warn_refresh(&mut self) {
+        self.send(
+            json!({"type": "disconnect", "reason": "warning", "debug_info": {"host": "fake"}}),
+        )
+        .await;
+    }
+
+    /// Asks the client to reconnect, as Slack does periodically.
+    pub async fn request_refresh(&mut self) {
+        self.send(json!({
+            "type": "disconnect",
+            "reason": "refresh_requested",
+            "debug_info": {"host": "fake"}
+        }))
+        .await;
+    }
+
+    /// Waits for the next acknowledgement from the client.
+    pub async fn recv_ack(&mut self) -> Value {
+        loop {
+            let message = tokio::time::timeout(TIMEOUT, self.socket.next())
+                .await
+                .expect("応答がありませんでした")
+                .expect("接続が閉じられました")
+                .unwrap();
+            match message {
+                Message::Text(text) => return serde_json::from_str(text.as_str()).unwrap(),
+                Message::Close(_) => panic!("応答の前に接続が閉じられました"),
+                _ => {}
+            }
+        }
+    }
+
+    /// Waits for the client to close the connection, failing on any further acknowledgement.
+    pub async fn expect_close(&mut self) {
+        loop {
+            let message = tokio::time::timeout(TIMEOUT, self.socket.next())
+                .await
+                .expect("接続が閉じられませんでした");
+            match message {
+                None | Some(Ok(Message::Close(_))) | Some(Err(_)) => return,
+                Some(Ok(Message::Text(text))) => panic!("予期しない応答: {}", text),
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,1104 @@
+//! Socket Mode implementation for Slack WebSocket connections.
+//! This module is only available when the "socket_mode" feature is enabled.
+
+use crate::retry::RetryConfig;
+use crate::types::{InvalidTokenKind, Token, TokenKind};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use slack_morphism::errors::SlackClientError;
+use slack_morphism::prelude::*;
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+#[cfg(any(test, feature = "testing"))]
+pub mod fake_server;
+#[cfg(any(test, feature = "testing"))]
+pub use fake_server::{FakeConnection, FakeSocketModeServer};
+
+/// API error codes from `apps.connections.open` that retrying cannot fix.
+const FATAL_ERRORS: &[&str] = &[
+    "invalid_auth",
+    "not_authed",
+    "account_inactive",
+    "token_revoked",
+    "not_allowed_token_type",
+];
+
+/// An event received over Socket Mode.
+///
+/// Serializes as `{"type": "events_api", "payload": {...}}`, mirroring the Socket Mode envelope types.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum SocketModeEvent {
+    /// An Events API event (`events_api`).
+    EventsApi(SlackPushEventCallback),
+    /// A slash command (`slash_commands`).
+    SlashCommands(SlackCommandEvent),
+    /// A block action, shortcut or view submission (`interactive`).
+    Interactive(SlackInteractionEvent),
+}
+
+/// Handles events received over Socket Mode.
+///
+/// Each event is handled in its own task, so a slow handler does not hold up the connection.
+/// Envelopes that do not accept a response payload (such as `events_api`) are acknowledged
+/// as soon as they arrive, so Slack does not redeliver them while the handler runs past its
+/// 3 second deadline. Envelopes that accept one (slash commands and some interactions) are
+/// acknowledged once `handle` returns.
+#[async_trait]
+pub trait SocketModeHandler: Send + Sync + 'static {
+    /// Handles an event.
+    ///
+    /// If the envelope accepts a response payload, the returned value is sent as the payload
+    /// of the acknowledgement, which for slash commands becomes the command response;
+    /// otherwise it is discarded. Errors are logged and the event is still acknowledged so
+    /// that Slack does not redeliver it.
+    async fn handle(&self, event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>>;
+
+    /// Handles the raw payload of an `events_api`, `slash_commands` or `interactive` envelope.
+    ///
+    /// By default the payload is parsed into a [`SocketModeEvent`] and passed to
+    /// [`handle`](Self::handle). Override this to read fields the typed events drop.
+    async fn handle_payload(
+        &self,
+        kind: &str,
+        payload: Value,
+    ) -> Result<Option<Value>, Box<dyn Error>> {
+        let event = parse_event(kind, payload)
+            .map_err(|e| format!("failed to parse {} payload: {}", kind, e))?;
+        self.handle(event).await
+    }
+}
+
+/// Forwards every event to the channel and acknowledges it with an empty payload.
+#[async_trait]
+impl SocketModeHandler for mpsc::UnboundedSender<SocketModeEvent> {
+    async fn handle(&self, event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>> {
+        self.send(event)
+            .map_err(|_| "the event receiver has been dropped")?;
+        Ok(None)
+    }
+}
+
+/// The handler used by [`SocketModeClient::connect`], which only logs events.
+struct LoggingHandler;
+
+#[async_trait]
+impl SocketModeHandler for LoggingHandler {
+    async fn handle(&self, event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>> {
+        info!("Received Socket Mode event: {:?}", event);
+        Ok(None)
+    }
+}
+
+/// Options for Socket Mode connections.
+#[derive(Debug, Clone)]
+pub struct SocketModeOptions {
+    /// Number of WebSocket connections kept open in parallel (Slack allows up to 10).
+    ///
+    /// Slack delivers each event over only one of them, so extra connections keep events
+    /// flowing while another one is reconnecting.
+    pub connections: usize,
+    /// Backoff between reconnection attempts.
+    ///
+    /// The delay is reset once a connection is established; after `max_retries`
+    /// consecutive failures the connection gives up.
+    pub backoff: RetryConfig,
+    /// How long [`SocketModeHandle::shutdown`] waits for in-flight events.
+    pub shutdown_timeout: Duration,
+}
+
+impl Default for SocketModeOptions {
+    fn default() -> Self {
+        Self {
+            connections: 1,
+            backoff: RetryConfig {
+                max_retries: usize::MAX,
+                initial_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(30),
+            },
+            shutdown_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// The state of a Socket Mode client, published through [`SocketModeHandle::state`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The first connection is being opened.
+    Connecting,
+    /// At least one connection has received Slack's `hello`.
+    Connected {
+        /// Number of open connections.
+        connections: usize,
+    },
+    /// Every connection was lost and is being reopened.
+    Reconnecting {
+        /// Why the last connection was lost.
+        reason: String,
+    },
+    /// The client has stopped and will not reconnect.
+    Disconnected {
+        /// Why the client stopped.
+        reason: String,
+    },
+}
+
+/// Aggregates the state of every connection into a single [`ConnectionState`].
+struct StateReporter {
+    open: AtomicUsize,
+    /// Number of connection tasks that have not given up.
+    running: AtomicUsize,
+    sender: watch::Sender<ConnectionState>,
+}
+
+impl StateReporter {
+    fn new(connections: usize) -> Self {
+        Self {
+            open: AtomicUsize::new(0),
+            running: AtomicUsize::new(connections),
+            sender: watch::Sender::new(ConnectionState::Connecting),
+        }
+    }
+
+    fn opened(&self) {
+        let connections = self.open.fetch_add(1, Ordering::SeqCst) + 1;
+        self.sender
+            .send_replace(ConnectionState::Connected { connections });
+    }
+
+    fn closed(&self, reason: &str) {
+        let connections = self.open.fetch_sub(1, Ordering::SeqCst) - 1;
+        self.sender.send_replace(if connections > 0 {
+            ConnectionState::Connected { connections }
+        } else {
+            ConnectionState::Reconnecting {
+                reason: reason.to_string(),
+            }
+        });
+    }
+
+    fn reconnecting(&self, reason: &str) {
+        if self.open.load(Ordering::SeqCst) == 0 {
+            self.sender.send_replace(ConnectionState::Reconnecting {
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    /// Records that one connection gave up; the client is disconnected once all of them have.
+    fn gave_up(&self, reason: &str) {
+        if self.running.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.disconnected(reason);
+        }
+    }
+
+    fn disconnected(&self, reason: &str) {
+        self.sender.send_replace(ConnectionState::Disconnected {
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// A Socket Mode message sent by Slack.
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(rename = "type")]
+    kind: String,
+    envelope_id: Option<String>,
+    payload: Option<Value>,
+    /// Whether the acknowledgement may carry a response (slash commands, some interactions).
+    #[serde(default)]
+    accepts_response_payload: bool,
+    reason: Option<String>,
+}
+
+/// Envelope types that carry an event and must be acknowledged.
+const EVENT_KINDS: &[&str] = &["events_api", "slash_commands", "interactive"];
+
+/// Parses the payload of an event envelope.
+fn parse_event(kind: &str, payload: Value) -> Result<SocketModeEvent, serde_json::Error> {
+    match kind {
+        "events_api" => serde_json::from_value(payload).map(SocketModeEvent::EventsApi),
+        "slash_commands" => serde_json::from_value(payload).map(SocketModeEvent::SlashCommands),
+        _ => serde_json::from_value(payload).map(SocketModeEvent::Interactive),
+    }
+}
+
+/// Builds the acknowledgement for an envelope.
+fn ack(envelope_id: &str, payload: Option<Value>) -> Message {
+    let mut ack = serde_json::json!({ "envelope_id": envelope_id });
+    if let Some(payload) = payload {
+        ack["payload"] = payload;
+    }
+    Message::text(ack.to_string())
+}
+
+/// Why a single WebSocket session ended.
+enum SessionEnd {
+    /// Shutdown was requested and in-flight events have been drained.
+    Shutdown,
+    /// The connection was lost or Slack asked us to reconnect.
+    Lost {
+        reason: String,
+        /// Whether the session got as far as Slack's `hello`.
+        established: bool,
+        /// Whether Slack sent a `disconnect`, in which case we reconnect without waiting.
+        requested: bool,
+    },
+}
+
+/// State shared by every connection of a client.
+struct Connector {
+    client: SlackHyperClient,
+    token: SlackApiToken,
+    handler: Arc<dyn SocketModeHandler>,
+    options: SocketModeOptions,
+    state: StateReporter,
+}
+
+impl Connector {
+    /// Requests a WebSocket URL; the flag tells whether the error is worth retrying.
+    async fn open_url(&self) -> Result<String, (String, bool)> {
+        let session = self.client.open_session(&self.token);
+        match session
+            .apps_connections_open(&SlackApiAppsConnectionOpenRequest::new())
+            .await
+        {
+            Ok(response) => Ok(response.url.0.to_string()),
+            Err(SlackClientError::ApiError(e)) if FATAL_ERRORS.contains(&e.code.as_str()) => {
+                Err((e.code, false))
+            }
+            Err(e) => Err((e.to_string(), true)),
+        }
+    }
+
+    /// Keeps one connection open, reconnecting with backoff until shutdown or a fatal error.
+    async fn run(self: Arc<Self>, id: usize, mut shutdown: watch::Receiver<bool>) {
+        let mut attempt = 0;
+        loop {
+            if *shutdown.borrow() {
+                return;
+            }
+            let reason = match self.open_url().await {
+                // Fatal errors concern the token, so every other connection fails the same way
+                Err((reason, false)) => {
+                    warn!("Socket Mode connection {} failed: {}", id, reason);
+                    self.state.disconnected(&reason);
+                    return;
+                }
+                Err((reason, true)) => reason,
+                Ok(url) => match tokio_tungstenite::connect_async(url.as_str()).await {
+                    Err(e) => e.to_string(),
+                    Ok((socket, _)) => match self.session(id, socket, &mut shutdown).await {
+                        SessionEnd::Shutdown => return,
+                        SessionEnd::Lost {
+                            reason,
+                            requested: true,
+                            ..
+                        } => {
+                            info!("Socket Mode connection {} refreshing: {}", id, reason);
+                            attempt = 0;
+                            self.state.reconnecting(&reason);
+                            continue;
+                        }
+                        SessionEnd::Lost {
+                            reason,
+                            established,
+                            ..
+                        } => {
+                            if established {
+                                attempt = 0;
+                            }
+                            reason
+                        }
+                    },
+                },
+            };
+
+            warn!("Socket Mode connection {} lost: {}", id, reason);
+            self.state.reconnecting(&reason);
+            let Some(delay) = self.options.backoff.delay(attempt, None) else {
+                warn!("Socket Mode connection {} gave up: {}", id, reason);
+                self.state
+                    .gave_up(&format!("gave up after {} retries: {}", attempt, reason));
+                return;
+            };
+            attempt += 1;
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = shutdown.changed() => return,
+            }
+        }
+    }
+
+    /// Reads envelopes from one WebSocket until it closes or shutdown is requested.
+    async fn session<S>(
+        &self,
+        id: usize,
+        socket: tokio_tungstenite::WebSocketStream<S>,
+        shutdown: &mut watch::Receiver<bool>,
+    ) -> SessionEnd
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        let (mut sink, mut stream) = socket.split();
+        let (acks, mut pending_acks) = mpsc::unbounded_channel::<Message>();
+        // Each handler task holds a clone of `in_flight`; `drained` resolves once all are dropped.
+        let (in_flight, mut drained) = mpsc::channel::<()>(1);
+        let mut established = false;
+        let mut requested = false;
+
+        let reason = loop {
+            tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        let envelope: Envelope = match serde_json::from_str(text.as_str()) {
+                            Ok(envelope) => envelope,
+                            Err(e) => {
+                                warn!("Invalid Socket Mode message: {}", e);
+                                continue;
+                            }
+                        };
+                        match envelope.kind.as_str() {
+                            "hello" => {
+                                info!("Socket Mode connection {} established", id);
+                                established = true;
+                                self.state.opened();
+                            }
+                            // Slack warns a few seconds before asking us to refresh the connection
+                            "disconnect" if envelope.reason.as_deref() == Some("warning") => {
+                                debug!("Socket Mode connection {} will be refreshed soon", id);
+                            }
+                            "disconnect" => {
+                                requested = true;
+                                sink.send(Message::Close(None)).await.ok();
+                                break envelope
+                                    .reason
+                                    .unwrap_or_else(|| "disconnect requested".to_string());
+                            }
+                            _ => self.dispatch(envelope, &acks, &in_flight),
+                        }
+                    }
+                    Some(Ok(Message::Close(frame))) => {
+                        break frame.map_or_else(
+                            || "connection closed".to_string(),
+                            |frame| format!("connection closed: {}", frame),
+                        );
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => break e.to_string(),
+                    None => break "connection closed".to_string(),
+                },
+                Some(message) = pending_acks.recv() => {
+                    if let Err(e) = sink.send(message).await {
+                        break e.to_string();
+                    }
+                }
+                _ = shutdown.changed() => {
+                    self.drain(id, &mut sink, &mut pending_acks, in_flight, &mut drained)
+                        .await;
+                    if established {
+                        self.state.closed("shutdown");
+                    }
+                    return SessionEnd::Shutdown;
+                }
+            }
+        };
+
+        if established {
+            self.state.closed(&reason);
+        }
+        SessionEnd::Lost {
+            reason,
+            established,
+            requested,
+        }
+    }
+
+    /// Runs the handler for an event envelope in its own task and queues the acknowledgement.
+    ///
+    /// The acknowledgement is queued right away unless the envelope accepts a response payload,
+    /// in which case it waits for the handler's response.
+    fn dispatch(
+        &self,
+        envelope: Envelope,
+        acks: &mpsc::UnboundedSender<Message>,
+        in_flight: &mpsc::Sender<()>,
+    ) {
+        let (Some(envelope_id), true) = (
+            envelope.envelope_id,
+            EVENT_KINDS.contains(&envelope.kind.as_str()),
+        ) else {
+            debug!("Ignoring Socket Mode message: {}", envelope.kind);
+            return;
+        };
+
+        let respond = envelope.accepts_response_payload;
+        if !respond {
+            acks.send(ack(&envelope_id, None)).ok();
+        }
+        let handler = self.handler.clone();
+        let acks = acks.clone();
+        let guard = in_flight.clone();
+        tokio::spawn(async move {
+            let payload = envelope.payload.unwrap_or_default();
+            let result = handler
+                .handle_payload(&envelope.kind, payload)
+                .await
+                .map_err(|e| e.to_string());
+            let payload = match result {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Socket Mode handler failed: {}", e);
+                    None
+                }
+            };
+            if respond {
+                // If the connection has gone away Slack redelivers the event elsewhere.
+                acks.send(ack(&envelope_id, payload)).ok();
+            } else if payload.is_some() {
+                debug!(
+                    "Discarding the response to a {} envelope, which does not accept one",
+                    envelope.kind
+                );
+            }
+            drop(guard);
+        });
+    }
+
+    /// Waits for in-flight events, sends their acknowledgements and closes the socket.
+    async fn drain<S>(
+        &self,
+        id: usize,
+        sink: &mut S,
+        pending_acks: &mut mpsc::UnboundedReceiver<Message>,
+        in_flight: mpsc::Sender<()>,
+        drained: &mut mpsc::Receiver<()>,
+    ) where
+        S: futures::Sink<Message> + Unpin,
+    {
+        drop(in_flight);
+        let wait = async {
+            loop {
+                tokio::select! {
+                    Some(message) = pending_acks.recv() => {
+                        sink.send(message).await.ok();
+                    }
+                    _ = drained.recv() => break,
+                }
+            }
+        };
+        if tokio::time::timeout(self.options.shutdown_timeout, wait)
+            .await
+            .is_err()
+        {
+            warn!(
+                "Socket Mode connection {} closed with events still in flight",
+                id
+            );
+        }
+        while let Ok(message) = pending_acks.try_recv() {
+            sink.send(message).await.ok();
+        }
+        sink.send(Message::Close(None)).await.ok();
+    }
+}
+
+/// A handle to running Socket Mode connections.
+///
+/// Dropping the handle stops the connections without waiting for them;
+/// call [`shutdown`](Self::shutdown) to stop gracefully.
+pub struct SocketModeHandle {
+    shutdown: watch::Sender<bool>,
+    connector: Arc<Connector>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl SocketModeHandle {
+    /// Returns a receiver for connection state changes.
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.connector.state.sender.subscribe()
+    }
+
+    /// Stops the connections gracefully.
+    ///
+    /// Stops reading new events, waits up to
+    /// [`shutdown_timeout`](SocketModeOptions::shutdown_timeout) for in-flight events to be
+    /// handled and acknowledged, then closes the sockets.
+    pub async fn shutdown(mut self) {
+        info!("Shutting down Socket Mode connections");
+        self.shutdown.send_replace(true);
+        for task in std::mem::take(&mut self.tasks) {
+            task.await.ok();
+        }
+        if !matches!(
+            *self.connector.state.sender.borrow(),
+            ConnectionState::Disconnected { .. }
+        ) {
+            self.connector.state.disconnected("shutdown");
+        }
+    }
+
+    /// Waits until the client stops on its own, i.e. reaches [`ConnectionState::Disconnected`].
+    pub async fn closed(&self) -> ConnectionState {
+        let mut state = self.state();
+        state
+            .wait_for(|state| matches!(state, ConnectionState::Disconnected { .. }))
+            .await
+            .map(|state| state.clone())
+            .unwrap_or_else(|_| ConnectionState::Disconnected {
+                reason: "shutdown".to_string(),
+            })
+    }
+}
+
+impl Drop for SocketModeHandle {
+    fn drop(&mut self) {
+        self.shutdown.send_replace(true);
+    }
+}
+
+/// A client for Slack's Socket Mode connections.
+pub struct SocketModeClient {
+    app_token: Token,
+    options: SocketModeOptions,
+    api_url: Option<String>,
+}
+
+impl SocketModeClient {
+    /// Creates a new Socket Mode client with the given app token.
+    ///
+    /// Socket Mode requires an app-level token (`xapp-`); any other kind is rejected.
+    pub fn new(app_token: &str) -> Result<Self, InvalidTokenKind> {
+        let app_token = Token::new(app_token);
+        app_token.require(TokenKind::App)?;
+        Ok(Self {
+            app_token,
+            options: SocketModeOptions::default(),
+            api_url: None,
+        })
+    }
+
+    /// Sets the connection options.
+    pub fn with_options(mut self, options: SocketModeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the base URL of the Web API used to call `apps.connections.open`.
+    ///
+    /// Defaults to `https://slack.com/api`. Unlike the default, a custom URL may use plain
+    /// `http://`, e.g. for a proxy or a local test server.
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = Some(api_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Creates the HTTP client for the Web API.
+    fn http_client(&self) -> Result<SlackHyperClient, Box<dyn Error>> {
+        let connector = match &self.api_url {
+            None => SlackClientHyperConnector::new()?,
+            Some(api_url) => SlackClientHyperConnector::from(
+                hyper_rustls::HttpsConnectorBuilder::new()
+                    .with_native_roots()?
+                    .https_or_http()
+                    .enable_http1()
+                    .enable_http2()
+                    .build(),
+            )
+            .with_slack_api_url(api_url),
+        };
+        Ok(SlackClient::new(connector))
+    }
+
+    /// Connects to Slack's Socket Mode WebSocket server and logs every received event.
+    ///
+    /// See [`connect_with`](Self::connect_with).
+    pub async fn connect(&self) -> Result<SocketModeHandle, Box<dyn Error>> {
+        self.connect_with(LoggingHandler).await
+    }
+
+    /// Connects to Slack's Socket Mode WebSocket server and passes every event to `handler`.
+    ///
+    /// Returns once the first connection is established. Transient failures are retried in
+    /// the background; an error is returned only if the client gives up, e.g. because the
+    /// token is invalid.
+    pub async fn connect_with(
+        &self,
+        handler: impl SocketModeHandler,
+    ) -> Result<SocketModeHandle, Box<dyn Error>> {
+        let connector = Arc::new(Connector {
+            client: self.http_client()?,
+            token: self.app_token.clone().into(),
+            handler: Arc::new(handler),
+            options: self.options.clone(),
+            state: StateReporter::new(self.options.connections.max(1)),
+        });
+        let (shutdown, shutdown_signal) = watch::channel(false);
+        let tasks = (0..self.options.connections.max(1))
+            .map(|id| tokio::spawn(connector.clone().run(id, shutdown_signal.clone())))
+            .collect();
+        let handle = SocketModeHandle {
+            shutdown,
+            connector,
+            tasks,
+        };
+
+        let mut state = handle.state();
+        let state = state
+            .wait_for(|state| {
+                matches!(
+                    state,
+                    ConnectionState::Connected { .. } | ConnectionState::Disconnected { .. }
+                )
+            })
+            .await?
+            .clone();
+        match state {
+            ConnectionState::Disconnected { reason } => {
+                Err(format!("Failed to connect to Socket Mode: {}", reason).into())
+            }
+            _ => Ok(handle),
+        }
+    }
+
+    /// Connects to Socket Mode and forwards every received event to `events`.
+    ///
+    /// Runs until the receiving half of `events` is dropped, then closes the connection.
+    /// Slash commands are acknowledged with an empty response.
+    pub async fn listen(
+        &self,
+        events: mpsc::UnboundedSender<SocketModeEvent>,
+    ) -> Result<(), Box<dyn Error>> {
+        let handle = self.connect_with(events.clone()).await?;
+        info!("Listening for Socket Mode events");
+        tokio::select! {
+            _ = events.closed() => {}
+            state = handle.closed() => {
+                if let ConnectionState::Disconnected { reason } = state {
+                    return Err(format!("Socket Mode connection stopped: {}", reason).into());
+                }
+            }
+        }
+        handle.shutdown().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake_server::FakeSocketModeServer;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_state_aggregates_connections() {
+        let state = StateReporter::new(2);
+        let receiver = state.sender.subscribe();
+        assert_eq!(*receiver.borrow(), ConnectionState::Connecting);
+
+        state.opened();
+        state.opened();
+        assert_eq!(
+            *receiver.borrow(),
+            ConnectionState::Connected { connections: 2 }
+        );
+
+        // One connection dropping leaves the client connected
+        state.closed("refresh_requested");
+        state.reconnecting("refresh_requested");
+        assert_eq!(
+            *receiver.borrow(),
+            ConnectionState::Connected { connections: 1 }
+        );
+
+        state.closed("link_disabled");
+        assert_eq!(
+            *receiver.borrow(),
+            ConnectionState::Reconnecting {
+                reason: "link_disabled".to_string()
+            }
+        );
+
+        state.disconnected("invalid_auth");
+        assert_eq!(
+            *receiver.borrow(),
+            ConnectionState::Disconnected {
+                reason: "invalid_auth".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_disconnected_only_when_every_connection_gave_up() {
+        let state = StateReporter::new(2);
+        let receiver = state.sender.subscribe();
+        state.opened();
+        state.opened();
+
+        // One connection giving up leaves the other serving events
+        state.closed("link_disabled");
+        state.gave_up("gave up after 3 retries: link_disabled");
+        assert_eq!(
+            *receiver.borrow(),
+            ConnectionState::Connected { connections: 1 }
+        );
+
+        state.closed("link_disabled");
+        assert!(matches!(
+            *receiver.borrow(),
+            ConnectionState::Reconnecting { .. }
+        ));
+        state.gave_up("gave up after 3 retries: link_disabled");
+        assert_eq!(
+            *receiver.borrow(),
+            ConnectionState::Disconnected {
+                reason: "gave up after 3 retries: link_disabled".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_envelope_and_ack() {
+        let envelope: Envelope = serde_json::from_str(
+            r#"{"type": "disconnect", "reason": "refresh_requested", "debug_info": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(envelope.reason.as_deref(), Some("refresh_requested"));
+        assert!(!EVENT_KINDS.contains(&envelope.kind.as_str()));
+
+        let envelope: Envelope = serde_json::from_str(
+            r#"{"type": "slash_commands", "envelope_id": "e1", "payload": {"bad": true}}"#,
+        )
+        .unwrap();
+        assert!(parse_event(&envelope.kind, envelope.payload.unwrap()).is_err());
+
+        assert_eq!(ack("e1", None), Message::text(r#"{"envelope_id":"e1"}"#));
+        assert_eq!(
+            ack("e1", Some(serde_json::json!({"text": "ok"}))),
+            Message::text(r#"{"envelope_id":"e1","payload":{"text":"ok"}}"#)
+        );
+    }
+
+    #[test]
+    fn test_default_options_retry_forever() {
+        let options = SocketModeOptions::default();
+        assert_eq!(
+            options.backoff.delay(10, None),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    /// Forwards events to the test and answers slash commands with their text.
+    struct EchoHandler(mpsc::UnboundedSender<SocketModeEvent>);
+
+    #[async_trait]
+    impl SocketModeHandler for EchoHandler {
+        async fn handle(&self, event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>> {
+            let response = match &event {
+                SocketModeEvent::SlashCommands(command) => Some(json!({
+                    "text": format!("{} {}", command.command, command.text.clone().unwrap_or_default())
+                })),
+                SocketModeEvent::EventsApi(_) | SocketModeEvent::Interactive(_) => None,
+            };
+            self.0.send(event).ok();
+            Ok(response)
+        }
+    }
+
+    /// Takes a while to handle each event.
+    struct SlowHandler;
+
+    #[async_trait]
+    impl SocketModeHandler for SlowHandler {
+        async fn handle(&self, _event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>> {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(Some(json!({"text": "done"})))
+        }
+    }
+
+    /// Holds every event until the test releases it.
+    struct BlockedHandler(Arc<tokio::sync::Semaphore>);
+
+    #[async_trait]
+    impl SocketModeHandler for BlockedHandler {
+        async fn handle(&self, _event: SocketModeEvent) -> Result<Option<Value>, Box<dyn Error>> {
+            self.0.acquire().await?.forget();
+            Ok(Some(json!({"text": "done"})))
+        }
+    }
+
+    fn mention_payload() -> Value {
+        json!({
+            "token": "t",
+            "team_id": "T1",
+            "api_app_id": "A1",
+            "event": {
+                "type": "app_mention",
+                "user": "U1",
+                "text": "<@B1> hello",
+                "ts": "1700000000.000100",
+                "channel": "C1",
+                "event_ts": "1700000000.000100"
+            },
+            "type": "event_callback",
+            "event_id": "Ev1",
+            "event_time": 1700000000
+        })
+    }
+
+    fn command_payload() -> Value {
+        json!({
+            "token": "t",
+            "team_id": "T1",
+            "team_domain": "example",
+            "channel_id": "C1",
+            "channel_name": "general",
+            "user_id": "U1",
+            "user_name": "user",
+            "command": "/deploy",
+            "text": "production",
+            "api_app_id": "A1",
+            "response_url": "https://hooks.slack.com/commands/T1/1/abc",
+            "trigger_id": "1.2.abc"
+        })
+    }
+
+    fn block_actions_payload() -> Value {
+        json!({
+            "type": "block_actions",
+            "team": {"id": "T1", "domain": "example"},
+            "user": {"id": "U1", "name": "user"},
+            "api_app_id": "A1",
+            "container": {"type": "message", "message_ts": "1700000000.000100", "channel_id": "C1"},
+            "trigger_id": "1.2.abc",
+            "channel": {"id": "C1", "name": "general"},
+            "actions": [{
+                "type": "button",
+                "action_id": "approve",
+                "block_id": "b1",
+                "value": "yes",
+                "action_ts": "1700000001.000100"
+            }]
+        })
+    }
+
+    async fn wait_for_state(
+        state: &mut watch::Receiver<ConnectionState>,
+        expected: ConnectionState,
+    ) {
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            state.wait_for(|state| *state == expected),
+        )
+        .await
+        .unwrap_or_else(|_| panic!("{:?}になりませんでした", expected))
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_acknowledges_events_from_fake_server() {
+        let mut server = FakeSocketModeServer::start().await;
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let handle = server
+            .client()
+            .connect_with(EchoHandler(sender))
+            .await
+            .unwrap();
+        let mut connection = server.accept().await;
+        assert_eq!(
+            *handle.state().borrow(),
+            ConnectionState::Connected { connections: 1 }
+        );
+
+        connection
+            .send_event("events_api", "e1", mention_payload())
+            .await;
+        assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e1"}));
+        assert!(matches!(
+            events.recv().await,
+            Some(SocketModeEvent::EventsApi(_))
+        ));
+
+        // The handler's return value becomes the slash command response
+        connection
+            .send_event("slash_commands", "e2", command_payload())
+            .await;
+        assert_eq!(
+            connection.recv_ack().await,
+            json!({"envelope_id": "e2", "payload": {"text": "/deploy production"}})
+        );
+
+        connection
+            .send_event("interactive", "e3", block_actions_payload())
+            .await;
+        assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e3"}));
+        assert!(matches!(
+            events.recv().await,
+            Some(SocketModeEvent::SlashCommands(_))
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(SocketModeEvent::Interactive(_))
+        ));
+
+        // Payloads we cannot parse are still acknowledged so that Slack does not redeliver them
+        connection
+            .send_event("interactive", "e4", json!({"type": "unknown"}))
+            .await;
+        assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e4"}));
+
+        handle.shutdown().await;
+        connection.expect_close().await;
+    }
+
+    #[tokio::test]
+    async fn test_acknowledges_events_before_handling() {
+        let mut server = FakeSocketModeServer::start().await;
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        let handle = server
+            .client()
+            .connect_with(BlockedHandler(release.clone()))
+            .await
+            .unwrap();
+        let mut connection = server.accept().await;
+
+        // Events that cannot carry a response are acknowledged while the handler is still running
+        connection
+            .send_event("events_api", "e1", mention_payload())
+            .await;
+        assert_eq!(connection.recv_ack().await, json!({"envelope_id": "e1"}));
+
+        // Slash commands wait for the handler's response
+        connection
+            .send_event("slash_commands", "e2", command_payload())
+            .await;
+        release.add_permits(2);
+        assert_eq!(
+            connection.recv_ack().await,
+            json!({"envelope_id": "e2", "payload": {"text": "done"}})
+        );
+
+        handle.shutdown().await;
+        connection.expect_close().await;
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_on_refresh_request() {
+        let mut server = FakeSocketModeServer::start().await;
+        let (sender, _events) = mpsc::unbounded_channel();
+        let handle = server
+            .client()
+            .connect_with(EchoHandler(sender))
+            .await
+            .unwrap();
+        let mut state = handle.state();
+        let mut first = server.accept().await;
+
+        // A warning alone does not drop the connection
+        first.warn_refresh().await;
+        first
+            .send_event("events_api", "e1", mention_payload())
+            .await;
+        assert_eq!(first.recv_ack().await, json!({"envelope_id": "e1"}));
+        assert_eq!(server.opened(), 1);
+
+        // The client reconnects right away, without backoff
+        first.request_refresh().await;
+        first.expect_close().await;
+        let mut second = server.accept().await;
+        assert_eq!(server.opened(), 2);
+        wait_for_state(&mut state, ConnectionState::Connected { connections: 1 }).await;
+
+        second
+            .send_event("events_api", "e2", mention_payload())
+            .await;
+        assert_eq!(second.recv_ack().await, json!({"envelope_id": "e2"}));
+
+        handle.shutdown().await;
+        assert_eq!(
+            *state.borrow(),
+            ConnectionState::Disconnected {
+                reason: "shutdown".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parallel_connections() {
+        let mut server = FakeSocketModeServer::start().await;
+        let handle = server
+            .client()
+            .with_options(SocketModeOptions {
+                connections: 2,
+                ..Default::default()
+            })
+            .connect()
+            .await
+            .unwrap();
+        let mut state = handle.state();
+        let mut first = server.accept().await;
+        let mut second = server.accept().await;
+        wait_for_state(&mut state, ConnectionState::Connected { connections: 2 }).await;
+
+        // Losing one connection leaves the other serving events
+        first.request_refresh().await;
+        first.expect_close().await;
+        let mut third = server.accept().await;
+        wait_for_state(&mut state, ConnectionState::Connected { connections: 2 }).await;
+        assert_eq!(server.opened(), 3);
+
+        handle.shutdown().await;
+        second.expect_close().await;
+        third.expect_close().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_in_flight_events() {
+        let mut server = FakeSocketModeServer::start().await;
+        let handle = server.client().connect_with(SlowHandler).await.unwrap();
+        let mut connection = server.accept().await;
+
+        connection
+            .send_event("slash_commands", "e1", command_payload())
+            .await;
+        // Let the client read the envelope before shutting down
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let state = handle.state();
+        let shutdown = tokio::spawn(handle.shutdown());
+
+        assert_eq!(
+            connection.recv_ack().await,
+            json!({"envelope_id": "e1", "payload": {"text": "done"}})
+        );
+        connection.expect_close().await;
+        shutdown.await.unwrap();
+        assert!(matches!(
+            *state.borrow(),
+            ConnectionState::Disconnected { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_fails_to_connect() {
+        let server = FakeSocketModeServer::start().await;
+        let error = SocketModeClient::new("xapp-1-A1-revoked")
+            .unwrap()
+            .with_api_url(server.api_url())
+            .connect()
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("invalid_auth"));
+        assert_eq!(server.opened(), 0);
+    }
+}
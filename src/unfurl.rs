@@ -0,0 +1,252 @@
+//! 共有されたリンクをドメインや正規表現で振り分けてプレビューを生成する
+//!
+//! [`UnfurlRegistry`]は[`SlackEventHandler`]を実装しているため、
+//! そのままwebhookのハンドラとして登録できます。
+
+#[cfg(feature = "events")]
+use crate::{
+    blocks::Block,
+    events::{Event, SharedLink},
+    manifest::AppManifest,
+    message::MessageClient,
+    webhook::SlackEventHandler,
+};
+#[cfg(feature = "events")]
+use async_trait::async_trait;
+#[cfg(feature = "events")]
+use regex::Regex;
+#[cfg(feature = "events")]
+use std::{collections::HashMap, error::Error, sync::Arc};
+#[cfg(feature = "events")]
+use tracing::{info, warn};
+
+/// リンクのプレビューを生成するプロバイダー
+#[cfg(feature = "events")]
+#[async_trait]
+pub trait UnfurlProvider: Send + Sync + 'static {
+    /// リンクのプレビューを生成します
+    ///
+    /// # 戻り値
+    /// * `Ok(Some(blocks))` - プレビューのブロック
+    /// * `Ok(None)` - プレビューを表示しない
+    async fn unfurl(&self, link: &SharedLink) -> Result<Option<Vec<Block>>, Box<dyn Error>>;
+}
+
+/// リンクとプロバイダーの対応付け
+#[cfg(feature = "events")]
+#[derive(Clone)]
+enum Route {
+    /// ドメインが一致する（サブドメインを含む）
+    Domain(String),
+    /// URLが正規表現に一致する
+    Pattern(Regex),
+}
+
+#[cfg(feature = "events")]
+impl Route {
+    fn matches(&self, link: &SharedLink) -> bool {
+        match self {
+            Self::Domain(domain) => {
+                link.domain == *domain || link.domain.ends_with(&format!(".{}", domain))
+            }
+            Self::Pattern(pattern) => pattern.is_match(&link.url),
+        }
+    }
+}
+
+/// リンクをプロバイダーに振り分けるレジストリ
+///
+/// 登録した順に照合し、最初に一致したプロバイダーでプレビューを生成します。
+#[cfg(feature = "events")]
+#[derive(Clone, Default)]
+pub struct UnfurlRegistry {
+    routes: Arc<Vec<(Route, Arc<dyn UnfurlProvider>)>>,
+}
+
+#[cfg(feature = "events")]
+impl UnfurlRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ドメイン（サブドメインを含む）のリンクを処理するプロバイダーを登録します
+    pub fn domain(self, domain: &str, provider: impl UnfurlProvider) -> Self {
+        self.route(Route::Domain(domain.to_string()), provider)
+    }
+
+    /// URLが正規表現に一致するリンクを処理するプロバイダーを登録します
+    pub fn pattern(
+        self,
+        pattern: &str,
+        provider: impl UnfurlProvider,
+    ) -> Result<Self, regex::Error> {
+        Ok(self.route(Route::Pattern(Regex::new(pattern)?), provider))
+    }
+
+    fn route(mut self, route: Route, provider: impl UnfurlProvider) -> Self {
+        Arc::make_mut(&mut self.routes).push((route, Arc::new(provider)));
+        self
+    }
+
+    fn provider_for(&self, link: &SharedLink) -> Option<&dyn UnfurlProvider> {
+        self.routes
+            .iter()
+            .find(|(route, _)| route.matches(link))
+            .map(|(_, provider)| provider.as_ref())
+    }
+
+    /// リンクのプレビューを生成します
+    ///
+    /// プロバイダーのエラーはログに出力し、そのリンクのプレビューは省略します。
+    pub async fn build(&self, links: &[SharedLink]) -> HashMap<String, Vec<Block>> {
+        let mut unfurls = HashMap::new();
+        for link in links {
+            let Some(provider) = self.provider_for(link) else {
+                continue;
+            };
+            // `Box<dyn Error>`は`Send`ではないため、await前に文字列にしておく
+            match provider.unfurl(link).await.map_err(|e| e.to_string()) {
+                Ok(Some(blocks)) => {
+                    unfurls.insert(link.url.clone(), blocks);
+                }
+                Ok(None) => {}
+                Err(message) => {
+                    warn!(
+                        "リンクのプレビューの生成に失敗しました: {} ({})",
+                        link.url, message
+                    )
+                }
+            }
+        }
+        unfurls
+    }
+}
+
+#[cfg(feature = "events")]
+#[async_trait]
+impl SlackEventHandler for UnfurlRegistry {
+    /// `link_shared`イベントと`chat.unfurl`のスコープ、ドメインで登録したプロバイダーのドメインを追加します
+    ///
+    /// 正規表現で登録したプロバイダーのドメインは推定できないため、
+    /// [`AppManifest::with_unfurl_domain`]で追加してください。
+    fn manifest(&self, manifest: AppManifest) -> AppManifest {
+        self.routes.iter().fold(
+            manifest
+                .with_bot_event("link_shared")
+                .with_api_method("chat.unfurl"),
+            |manifest, (route, _)| match route {
+                Route::Domain(domain) => manifest.with_unfurl_domain(domain.as_str()),
+                Route::Pattern(_) => manifest,
+            },
+        )
+    }
+
+    async fn handle_event(
+        &self,
+        event: Event,
+        client: &MessageClient,
+    ) -> Result<(), Box<dyn Error>> {
+        let Event::LinkShared {
+            channel,
+            message_ts,
+            links,
+            unfurl_id,
+            source,
+            ..
+        } = event
+        else {
+            return Ok(());
+        };
+        let unfurls = self.build(&links).await;
+        if unfurls.is_empty() {
+            return Ok(());
+        }
+        info!("リンクのプレビューを生成しました: {}件", unfurls.len());
+        match (unfurl_id, source, message_ts) {
+            (Some(unfurl_id), Some(source), _) => {
+                client.unfurl_by_id(&unfurl_id, &source, &unfurls).await
+            }
+            (_, _, Some(message_ts)) => client.unfurl(&channel, &message_ts, &unfurls).await,
+            _ => Err("unfurl_idとmessage_tsのどちらもありません".into()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "events"))]
+mod tests {
+    use super::*;
+    use crate::TextObject;
+
+    struct IssueProvider;
+
+    #[async_trait]
+    impl UnfurlProvider for IssueProvider {
+        async fn unfurl(&self, link: &SharedLink) -> Result<Option<Vec<Block>>, Box<dyn Error>> {
+            let id = link.url.rsplit('/').next().unwrap_or_default();
+            if id == "broken" {
+                return Err("not found".into());
+            }
+            Ok(Some(vec![Block::section(TextObject::plain(format!(
+                "Issue {}",
+                id
+            )))]))
+        }
+    }
+
+    fn link(domain: &str, url: &str) -> SharedLink {
+        SharedLink {
+            domain: domain.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_links_by_domain_and_pattern() {
+        let registry = UnfurlRegistry::new()
+            .pattern(r"^https://git\.example\.com/.+/issues/\d+$", IssueProvider)
+            .unwrap()
+            .domain("tracker.example.com", IssueProvider);
+
+        let unfurls = registry
+            .build(&[
+                link("git.example.com", "https://git.example.com/app/issues/12"),
+                link("git.example.com", "https://git.example.com/app/wiki"),
+                link(
+                    "eu.tracker.example.com",
+                    "https://eu.tracker.example.com/34",
+                ),
+                link("tracker.example.com", "https://tracker.example.com/broken"),
+                link("other.com", "https://other.com/56"),
+            ])
+            .await;
+
+        let mut urls: Vec<_> = unfurls.keys().map(String::as_str).collect();
+        urls.sort();
+        assert_eq!(
+            urls,
+            vec![
+                "https://eu.tracker.example.com/34",
+                "https://git.example.com/app/issues/12"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manifest() {
+        let registry = UnfurlRegistry::new()
+            .domain("example.com", IssueProvider)
+            .pattern(r"^https://git\.example\.org/", IssueProvider)
+            .unwrap();
+        let manifest = AppManifest::new("Bot").with_handler(&registry);
+        assert_eq!(
+            manifest
+                .features
+                .unfurl_domains
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["example.com"]
+        );
+        assert!(manifest.oauth_config.scopes.bot.contains("links:write"));
+        assert!(manifest.oauth_config.scopes.bot.contains("links:read"));
+    }
+}
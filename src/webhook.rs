@@ -1,6 +1,7 @@
 #[cfg(feature = "events")]
 use crate::events::Event;
 use crate::{
+    manifest::AppManifest,
     message::MessageClient,
     types::{SigningSecret, Token},
 };
@@ -14,9 +15,11 @@ use axum::{
     Router,
 };
 use bytes::Bytes;
-use slack_morphism::{
-    events::SlackPushEvent as MorphismPushEvent, signature_verifier::SlackEventSignatureVerifier,
-};
+#[cfg(feature = "events")]
+use serde_json::Value;
+#[cfg(feature = "events")]
+use slack_morphism::events::{SlackCommandEvent, SlackInteractionEvent};
+use slack_morphism::signature_verifier::SlackEventSignatureVerifier;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // SlackApiSignatureVerifier is already available through prelude
@@ -58,10 +61,52 @@ pub trait SlackEventHandler: Send + Sync + Clone + 'static {
         event: Event,
         client: &MessageClient,
     ) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// スラッシュコマンドを処理します
+    ///
+    /// [`SlackApp`](crate::SlackApp)から呼び出されます。`Some`を返すと、その値を
+    /// コマンドへの応答（`text`や`blocks`、`response_type`を持つJSON）として返します。
+    /// 既定では何もせず、応答も返しません。
+    #[cfg(feature = "events")]
+    async fn handle_command(
+        &self,
+        _command: SlackCommandEvent,
+        _client: &MessageClient,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        Ok(None)
+    }
+
+    /// ボタンやモーダルなどのインタラクションを処理します
+    ///
+    /// [`SlackApp`](crate::SlackApp)から呼び出されます。`Some`を返すと、その値を
+    /// 応答（モーダル送信時の`response_action`など）として返します。
+    /// 既定では何もせず、応答も返しません。
+    #[cfg(feature = "events")]
+    async fn handle_interaction(
+        &self,
+        _interaction: SlackInteractionEvent,
+        _client: &MessageClient,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        Ok(None)
+    }
+
+    /// このハンドラが必要とするイベント・スコープなどをマニフェストに追加します
+    ///
+    /// [`AppManifest::with_handler`]と[`SlackApp::manifest`](crate::SlackApp::manifest)から
+    /// 呼び出されます。[`handle_command`](Self::handle_command)で処理するスラッシュコマンドや、
+    /// [`handle_interaction`](Self::handle_interaction)を使う場合のインタラクションもここで宣言します。
+    /// 既定では何も追加しません。
+    fn manifest(&self, manifest: AppManifest) -> AppManifest {
+        manifest
+    }
 }
 
 /// デフォルトのwebhookエンドポイントパス
 pub const DEFAULT_WEBHOOK_PATH: &str = "/push";
+/// インタラクション（ボタンやモーダル）のエンドポイントパスの既定値
+pub const DEFAULT_INTERACTIONS_PATH: &str = "/interactions";
+/// スラッシュコマンドのエンドポイントパスの既定値
+pub const DEFAULT_COMMANDS_PATH: &str = "/commands";
 
 #[derive(Clone)]
 #[cfg(feature = "events")]
@@ -97,51 +142,12 @@ pub async fn handle_push_event<H: SlackEventHandler>(
     let body_str = String::from_utf8(body.to_vec()).unwrap_or_default();
     tracing::debug!("受信したボディ: {}", body_str);
 
-    // 署名の検証
-    let signature = headers
-        .get(SlackEventSignatureVerifier::SLACK_SIGNED_HASH_HEADER)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-
-    let timestamp = headers
-        .get(SlackEventSignatureVerifier::SLACK_SIGNED_TIMESTAMP)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-
-    // タイムスタンプの検証（5分以上古いリクエストは拒否）
-    let timestamp_num = timestamp.parse::<u64>().unwrap_or(0);
-    let current_time = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    if current_time.abs_diff(timestamp_num) > 300 {
-        tracing::error!("リクエストが古すぎます: timestamp={}", timestamp);
-        return Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::from("Request timestamp is too old"))
-            .unwrap();
-    }
-
-    tracing::debug!(
-        "署名を検証: signature={}, timestamp={}",
-        signature,
-        timestamp
-    );
-
-    // 署名の検証
-    let verifier = SlackEventSignatureVerifier::new(&state.signing_secret.into());
-    let verification_result = verifier.verify(signature, &body_str, timestamp).is_ok();
-
-    if !verification_result {
-        return Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::from("Invalid signature"))
-            .unwrap();
+    if let Err(response) = verify_request(&state.signing_secret, &headers, &body_str) {
+        return response;
     }
 
-    // ボディをSlackPushEventとしてパース
-    let morphism_event: MorphismPushEvent = match serde_json::from_str(&body_str) {
+    // ボディをイベントとしてパース
+    let event = match Event::from_json(&body_str) {
         Ok(event) => event,
         Err(e) => {
             tracing::error!("JSONのパースに失敗: {}", e);
@@ -152,10 +158,8 @@ pub async fn handle_push_event<H: SlackEventHandler>(
         }
     };
 
-    tracing::info!("Slackイベントを受信: type={:?}", morphism_event);
-    tracing::debug!("イベントの詳細: {:?}", morphism_event);
+    tracing::info!("Slackイベントを受信: type={:?}", event);
 
-    let event: Event = morphism_event.into();
     match &event {
         Event::UrlVerification { challenge } => {
             tracing::info!("URL検証イベントを受信: challenge={}", challenge);
@@ -173,7 +177,12 @@ pub async fn handle_push_event<H: SlackEventHandler>(
                 .body(Body::from(challenge.clone()))
                 .unwrap()
         }
-        Event::AppMention { .. } | Event::Message { .. } => {
+        Event::AppMention { .. }
+        | Event::Message { .. }
+        | Event::LinkShared { .. }
+        | Event::MessageMetadataPosted { .. }
+        | Event::MessageMetadataUpdated { .. }
+        | Event::MessageMetadataDeleted { .. } => {
             tracing::info!("イベントコールバックを受信");
             if let Err(e) = state
                 .handler
@@ -197,6 +206,60 @@ pub async fn handle_push_event<H: SlackEventHandler>(
     }
 }
 
+/// リクエストの署名とタイムスタンプを検証します
+///
+/// 検証に失敗した場合は401 Unauthorizedのレスポンスを返します。
+#[cfg(feature = "events")]
+pub(crate) fn verify_request(
+    signing_secret: &SigningSecret,
+    headers: &HeaderMap,
+    body: &str,
+) -> Result<(), Response<Body>> {
+    // 署名の検証
+    let signature = headers
+        .get(SlackEventSignatureVerifier::SLACK_SIGNED_HASH_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    let timestamp = headers
+        .get(SlackEventSignatureVerifier::SLACK_SIGNED_TIMESTAMP)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    // タイムスタンプの検証（5分以上古いリクエストは拒否）
+    let timestamp_num = timestamp.parse::<u64>().unwrap_or(0);
+    let current_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    if current_time.abs_diff(timestamp_num) > 300 {
+        tracing::error!("リクエストが古すぎます: timestamp={}", timestamp);
+        return Err(Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::from("Request timestamp is too old"))
+            .unwrap());
+    }
+
+    tracing::debug!(
+        "署名を検証: signature={}, timestamp={}",
+        signature,
+        timestamp
+    );
+
+    // 署名の検証
+    let verifier = SlackEventSignatureVerifier::new(&signing_secret.clone().into());
+    let verification_result = verifier.verify(signature, body, timestamp).is_ok();
+
+    if !verification_result {
+        return Err(Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::from("Invalid signature"))
+            .unwrap());
+    }
+    Ok(())
+}
+
 /// webhookエンドポイントを作成します。
 ///
 /// # Arguments
@@ -228,6 +291,9 @@ pub fn create_app_with_handler<H: SlackEventHandler>(
 /// * `bot_token` - Slackボットトークン
 /// * `handler` - イベントを処理するハンドラ
 /// * `path` - webhookエンドポイントのパス（例："/push" や "/slack/events"）
+///
+/// # Panics
+/// `bot_token`にアプリレベルトークン（`xapp-`）を指定した場合
 #[cfg(feature = "events")]
 pub fn create_app_with_path<H: SlackEventHandler>(
     signing_secret: SigningSecret,
@@ -237,7 +303,7 @@ pub fn create_app_with_path<H: SlackEventHandler>(
 ) -> Router {
     let state = AppState {
         signing_secret,
-        message_client: MessageClient::new(bot_token),
+        message_client: MessageClient::new(bot_token).expect("ボットトークンが正しくありません"),
         handler,
     };
     Router::new()